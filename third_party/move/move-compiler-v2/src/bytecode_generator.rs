@@ -513,6 +513,10 @@ impl<'env> Generator<'env> {
             Value::Bool(x) => Constant::Bool(*x),
             Value::ByteArray(x) => Constant::ByteArray(x.clone()),
             Value::AddressArray(x) => Constant::AddressArray(x.clone()),
+            Value::Error => {
+                self.internal_error(id, "erroneous constant value");
+                Constant::Bool(false)
+            },
             Value::Tuple(x) => {
                 if let Some(inner_ty) = ty.get_vector_element_type() {
                     Constant::Vector(
@@ -37,7 +37,7 @@ use move_model::{
     exp_generator::ExpGenerator,
     model::{FunId, FunctionEnv, GlobalEnv, Loc, ModuleId, StructId, TypeParameterKind},
     ty,
-    ty::{gen_get_ty_param_kinds, Type},
+    ty::{gen_get_ty_param_kinds, Type, TypeArgError},
 };
 use move_stackless_bytecode::{
     dataflow_analysis::{DataflowAnalysis, TransferFunctions},
@@ -517,7 +517,7 @@ impl<'a> Transformer<'a> {
     fn check_fun_inst(&self, id: AttrId, mid: ModuleId, fid: FunId, inst: &[Type]) {
         let ty_params = self.builder.fun_env.get_type_parameters();
         let fun_env = self.env().get_function(mid.qualified(fid));
-        let err_handler = |loc: &Loc, ty: &Type, msg: &str| {
+        let err_handler = |loc: &Loc, ty: &Type, msg: &TypeArgError| {
             self.error(
                 loc,
                 format!("type `{}` is {}", self.display_ty(ty), msg),
@@ -565,7 +565,7 @@ impl<'a> Transformer<'a> {
             inst,
             ty::gen_get_ty_param_kinds(&ty_params),
             self.gen_get_struct_sig(),
-            Some((&self.loc(id), |loc: &Loc, ty: &Type, msg: &str| {
+            Some((&self.loc(id), |loc: &Loc, ty: &Type, msg: &TypeArgError| {
                 self.error(
                     loc,
                     format!("type `{}` is {}", self.display_ty(ty), msg),
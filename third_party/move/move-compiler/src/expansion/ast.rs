@@ -284,6 +284,7 @@ pub enum SpecBlockMember_ {
         additional_exps: Vec<Exp>,
     },
     Function {
+        attributes: Attributes,
         uninterpreted: bool,
         name: FunctionName,
         signature: FunctionSignature,
@@ -1162,11 +1163,13 @@ impl AstDebug for SpecBlockMember_ {
                 });
             },
             SpecBlockMember_::Function {
+                attributes,
                 uninterpreted,
                 signature,
                 name,
                 body,
             } => {
+                attributes.ast_debug(w);
                 if *uninterpreted {
                     w.write("uninterpreted ")
                 } else if let FunctionBody_::Native = &body.value {
@@ -2011,15 +2011,18 @@ fn spec_member(context: &mut Context, sp!(loc, pm): P::SpecBlockMember) -> E::Sp
             }
         },
         PM::Function {
+            attributes: pattributes,
             name,
             uninterpreted,
             signature,
             body,
         } => {
+            let attributes = flatten_attributes(context, AttributePosition::Spec, pattributes);
             let (old_aliases, signature) = function_signature(context, signature);
             let body = function_body(context, body);
             context.set_to_outer_scope(old_aliases);
             EM::Function {
+                attributes,
                 uninterpreted,
                 name,
                 signature,
@@ -3080,7 +3080,11 @@ fn parse_spec_block_member(context: &mut Context) -> Result<SpecBlockMember, Box
     match context.tokens.peek() {
         Tok::Invariant => parse_invariant(context),
         Tok::Let => parse_spec_let(context),
-        Tok::Fun | Tok::Native => parse_spec_function(context),
+        Tok::Fun | Tok::Native => parse_spec_function(vec![], context),
+        Tok::NumSign => {
+            let attributes = parse_attributes(context)?;
+            parse_spec_function(attributes, context)
+        },
         Tok::Identifier => match context.tokens.content() {
             "assert" | "assume" | "decreases" | "aborts_if" | "aborts_with" | "succeeds_if"
             | "modifies" | "emits" | "ensures" | "requires" => parse_condition(context),
@@ -3268,11 +3272,14 @@ fn parse_invariant(context: &mut Context) -> Result<SpecBlockMember, Box<Diagnos
 }
 
 // Parse a specification function.
-//     SpecFunction = "define" <SpecFunctionSignature> ( "{" <Sequence> "}" | ";" )
-//                  | "native" "define" <SpecFunctionSignature> ";"
+//     SpecFunction = <Attributes> "define" <SpecFunctionSignature> ( "{" <Sequence> "}" | ";" )
+//                  | <Attributes> "native" "define" <SpecFunctionSignature> ";"
 //     SpecFunctionSignature =
 //         <Identifier> <OptionalTypeParameters> "(" Comma<Parameter> ")" ":" <Type>
-fn parse_spec_function(context: &mut Context) -> Result<SpecBlockMember, Box<Diagnostic>> {
+fn parse_spec_function(
+    attributes: Vec<Attributes>,
+    context: &mut Context,
+) -> Result<SpecBlockMember, Box<Diagnostic>> {
     let start_loc = context.tokens.start_loc();
     let native_opt = consume_optional_token_with_loc(context.tokens, Tok::Native)?;
     consume_token(context.tokens, Tok::Fun)?;
@@ -3319,6 +3326,7 @@ fn parse_spec_function(context: &mut Context) -> Result<SpecBlockMember, Box<Dia
         start_loc,
         context.tokens.previous_end_loc(),
         SpecBlockMember_::Function {
+            attributes,
             signature,
             uninterpreted,
             name,
@@ -628,6 +628,8 @@ pub mod known_attributes {
         Verification(VerificationAttribute),
         Native(NativeAttribute),
         Deprecation(DeprecationAttribute),
+        Operator(OperatorAttribute),
+        SpecFunction(SpecFunctionAttribute),
     }
 
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -659,6 +661,19 @@ pub mod known_attributes {
         Deprecated,
     }
 
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub enum OperatorAttribute {
+        // Marks a function as overload of a binary operator for a struct, as in
+        // `#[operator = b"+"]`
+        Overload,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub enum SpecFunctionAttribute {
+        // Marks a recursive spec function as having a termination hint, as in `#[decreases]`
+        Decreases,
+    }
+
     impl fmt::Display for AttributePosition {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             match self {
@@ -693,6 +708,10 @@ pub mod known_attributes {
                 DeprecationAttribute::DEPRECATED_NAME => {
                     Self::Deprecation(DeprecationAttribute::Deprecated)
                 },
+                OperatorAttribute::OPERATOR => Self::Operator(OperatorAttribute::Overload),
+                SpecFunctionAttribute::DECREASES => {
+                    Self::SpecFunction(SpecFunctionAttribute::Decreases)
+                },
                 _ => return None,
             })
         }
@@ -713,6 +732,8 @@ pub mod known_attributes {
             VerificationAttribute::add_attribute_names(table);
             NativeAttribute::add_attribute_names(table);
             DeprecationAttribute::add_attribute_names(table);
+            OperatorAttribute::add_attribute_names(table);
+            SpecFunctionAttribute::add_attribute_names(table);
         }
 
         fn name(&self) -> &str {
@@ -721,6 +742,8 @@ pub mod known_attributes {
                 Self::Verification(a) => a.name(),
                 Self::Native(a) => a.name(),
                 Self::Deprecation(a) => a.name(),
+                Self::Operator(a) => a.name(),
+                Self::SpecFunction(a) => a.name(),
             }
         }
 
@@ -730,6 +753,8 @@ pub mod known_attributes {
                 Self::Verification(a) => a.expected_positions(),
                 Self::Native(a) => a.expected_positions(),
                 Self::Deprecation(a) => a.expected_positions(),
+                Self::Operator(a) => a.expected_positions(),
+                Self::SpecFunction(a) => a.expected_positions(),
             }
         }
     }
@@ -900,4 +925,58 @@ pub mod known_attributes {
             }
         }
     }
+
+    impl OperatorAttribute {
+        const ALL_ATTRIBUTE_NAMES: [&'static str; 1] = [Self::OPERATOR];
+        pub const OPERATOR: &'static str = "operator";
+    }
+
+    impl AttributeKind for OperatorAttribute {
+        fn add_attribute_names(table: &mut BTreeSet<String>) {
+            for str in Self::ALL_ATTRIBUTE_NAMES {
+                table.insert(str.to_string());
+            }
+        }
+
+        fn name(&self) -> &str {
+            match self {
+                Self::Overload => Self::OPERATOR,
+            }
+        }
+
+        fn expected_positions(&self) -> &'static BTreeSet<AttributePosition> {
+            static OPERATOR_POSITIONS: Lazy<BTreeSet<AttributePosition>> =
+                Lazy::new(|| IntoIterator::into_iter([AttributePosition::Function]).collect());
+            match self {
+                Self::Overload => &OPERATOR_POSITIONS,
+            }
+        }
+    }
+
+    impl SpecFunctionAttribute {
+        const ALL_ATTRIBUTE_NAMES: [&'static str; 1] = [Self::DECREASES];
+        pub const DECREASES: &'static str = "decreases";
+    }
+
+    impl AttributeKind for SpecFunctionAttribute {
+        fn add_attribute_names(table: &mut BTreeSet<String>) {
+            for str in Self::ALL_ATTRIBUTE_NAMES {
+                table.insert(str.to_string());
+            }
+        }
+
+        fn name(&self) -> &str {
+            match self {
+                Self::Decreases => Self::DECREASES,
+            }
+        }
+
+        fn expected_positions(&self) -> &'static BTreeSet<AttributePosition> {
+            static DECREASES_POSITIONS: Lazy<BTreeSet<AttributePosition>> =
+                Lazy::new(|| IntoIterator::into_iter([AttributePosition::Spec]).collect());
+            match self {
+                Self::Decreases => &DECREASES_POSITIONS,
+            }
+        }
+    }
 }
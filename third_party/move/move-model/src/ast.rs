@@ -83,6 +83,12 @@ impl Attribute {
         }
     }
 
+    pub fn node_id(&self) -> NodeId {
+        match self {
+            Attribute::Assign(id, _, _) | Attribute::Apply(id, _, _) => *id,
+        }
+    }
+
     pub fn has(attrs: &[Attribute], pred: impl Fn(&Attribute) -> bool) -> bool {
         attrs.iter().any(pred)
     }
@@ -2117,6 +2123,8 @@ pub enum Value {
     AddressArray(Vec<Address>), // TODO: merge AddressArray to Vector type in the future
     Vector(Vec<Value>),
     Tuple(Vec<Value>),
+    /// A placeholder for a value which could not be determined because of errors.
+    Error,
 }
 
 // enables `env.display(&value)`
@@ -2131,6 +2139,7 @@ impl<'a> fmt::Display for EnvDisplay<'a, Value> {
             Value::AddressArray(array) => write!(f, "a{:?}", array),
             Value::Vector(array) => write!(f, "{:?}", array),
             Value::Tuple(array) => write!(f, "({:?})", array),
+            Value::Error => write!(f, "*error*"),
         }
     }
 }
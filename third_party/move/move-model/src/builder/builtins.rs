@@ -40,6 +40,7 @@ pub(crate) fn declare_builtins(trans: &mut ModelBuilder) {
         ty: num_t.clone(),
         value: Value::Number(value),
         visibility,
+        attributes: vec![],
     };
 
     {
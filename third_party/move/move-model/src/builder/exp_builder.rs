@@ -126,7 +126,10 @@ impl<'env, 'translator, 'module_translator> ExpTranslator<'env, 'translator, 'mo
             struct_cache.insert(
                 id,
                 (
-                    parent.parent.lookup_struct_fields(id.instantiate(vec![])),
+                    parent.parent.lookup_struct_fields(
+                        id.instantiate(vec![]),
+                        &parent.parent.lookup_struct_entry(id).loc,
+                    ),
                     parent.parent.lookup_struct_abilities(id),
                 ),
             );
@@ -610,7 +613,9 @@ impl<'env, 'translator, 'module_translator> ExpTranslator<'env, 'translator, 'mo
                 let sym = self.symbol_pool().make(n.value.as_str());
                 let abilities = self.parent.translate_abilities(a);
                 let loc = self.to_loc(&n.loc);
-                self.define_type_param(&loc, sym, ty, true /*report_errors*/);
+                // Duplicate names are reported by `ModelBuilder::check_distinct_type_params`
+                // once the declaration is defined.
+                self.define_type_param(&loc, sym, ty, false /*report_errors*/);
                 TypeParameter(
                     sym,
                     if is_phantom {
@@ -654,7 +659,7 @@ impl<'env, 'translator, 'module_translator> ExpTranslator<'env, 'translator, 'mo
     }
 
     /// Displays a call target for error messages.
-    fn display_call_target(&mut self, module: &Option<ModuleName>, name: Symbol) -> String {
+    pub fn display_call_target(&mut self, module: &Option<ModuleName>, name: Symbol) -> String {
         if let Some(m) = module {
             if m != &self.parent.parent.builtin_module() {
                 // Only print the module name if it is not the pseudo builtin module.
@@ -2342,8 +2347,14 @@ impl<'env, 'translator, 'module_translator> ExpTranslator<'env, 'translator, 'mo
 
                 // If not found, try to resolve as builtin constant.
                 let builtin_sym = self.parent.parent.builtin_qualified_symbol(&name.value);
+                let module_sym = self.parent.qualified_by_module(sym);
                 if let Some(entry) = self.parent.parent.const_table.get(&builtin_sym).cloned() {
-                    if self.is_visible(entry.visibility) {
+                    // An invisible builtin constant is reported as such, unless the name is
+                    // declared in this module.
+                    if self.is_visible(entry.visibility)
+                        || !(self.parent.parent.const_table.contains_key(&module_sym)
+                            || self.parent.parent.spec_var_table.contains_key(&module_sym))
+                    {
                         return self.translate_constant(
                             loc,
                             entry,
@@ -2354,7 +2365,7 @@ impl<'env, 'translator, 'module_translator> ExpTranslator<'env, 'translator, 'mo
                     }
                 }
                 // If not found, treat as global var in this module.
-                self.parent.qualified_by_module(sym)
+                module_sym
             },
         };
         if let Some(entry) = self.parent.parent.const_table.get(&global_var_sym).cloned() {
@@ -2429,6 +2440,23 @@ impl<'env, 'translator, 'module_translator> ExpTranslator<'env, 'translator, 'mo
         context: &ErrorMessageContext,
         sym: &QualifiedSymbol,
     ) -> ExpData {
+        if !entry.visible_in(self.mode == ExpTranslationMode::Spec) {
+            self.error(
+                loc,
+                &if self.mode == ExpTranslationMode::Spec {
+                    format!(
+                        "constant `{}` can only be used in code, not in specifications",
+                        sym.symbol.display(self.symbol_pool())
+                    )
+                } else {
+                    format!(
+                        "constant `{}` can only be used in specifications",
+                        sym.symbol.display(self.symbol_pool())
+                    )
+                },
+            );
+            return self.new_error_exp();
+        }
         // Constants are always visible in specs.
         if self.mode != ExpTranslationMode::Spec && sym.module_name != self.parent.module_name {
             self.error(
@@ -2444,6 +2472,7 @@ impl<'env, 'translator, 'module_translator> ExpTranslator<'env, 'translator, 'mo
             let ConstEntry { ty, value, .. } = entry;
             let ty = self.check_type(loc, &ty, expected_type, context);
             let id = self.new_node_id_with_type_loc(&ty, loc);
+            self.parent.parent.const_references.insert(id, sym.clone());
             ExpData::Value(id, value)
         }
     }
@@ -2579,7 +2608,7 @@ impl<'env, 'translator, 'module_translator> ExpTranslator<'env, 'translator, 'mo
                 let field_map = self
                     .parent
                     .parent
-                    .lookup_struct_fields(mid.qualified_inst(sid, inst));
+                    .lookup_struct_fields(mid.qualified_inst(sid, inst), loc);
                 let field_name = self.symbol_pool().make(name.value.as_str());
                 let expected_field_type =
                     field_map.get(&field_name).cloned().unwrap_or(Type::Error); // this error is reported via type unification
@@ -2621,26 +2650,32 @@ impl<'env, 'translator, 'module_translator> ExpTranslator<'env, 'translator, 'mo
         }
     }
 
-    /// Translates a call, performing overload resolution. Reports an error if the function cannot be found.
-    /// This is used to resolve both calls to user functions and builtin operators.
-    fn translate_call(
-        &mut self,
-        loc: &Loc,
+    /// Returns the functions which a call of `name`, qualified by `module` if given, may
+    /// resolve to in the current mode. An unqualified name is resolved both in this and in the
+    /// builtin pseudo module.
+    pub fn call_candidates(
+        &self,
         module: &Option<ModuleName>,
         name: Symbol,
-        generics: Option<&[EA::Type]>,
-        args: &[&EA::Exp],
-        expected_type: &Type,
-        context: &ErrorMessageContext,
-    ) -> ExpData {
-        // Translate generic arguments, if any.
-        let generics = generics.as_ref().map(|ts| self.translate_types(ts));
-        // Translate arguments.
-        let (arg_types, mut translated_args) = self.translate_exp_list(args);
-        let args_have_errors = arg_types.iter().any(|t| t == &Type::Error);
-        // Lookup candidates.
+        first_arg_ty: Option<&Type>,
+    ) -> Vec<AnyFunEntry> {
         let cand_modules = if let Some(m) = module {
-            vec![m.clone()]
+            let mut modules = vec![m.clone()];
+            // A binary operator applied to a struct is also resolved in the module of the
+            // struct, which may declare an overload of the operator.
+            if let (Some(op), Some(arg_ty)) = (
+                self.parent.parent.overloadable_bin_op(m, name),
+                first_arg_ty,
+            ) {
+                let op_module = self
+                    .parent
+                    .parent
+                    .resolve_op_module(&op, &self.subs.specialize(arg_ty));
+                if &op_module != m {
+                    modules.push(op_module)
+                }
+            }
+            modules
         } else {
             // For an unqualified name, resolve it both in this and in the builtin pseudo module.
             vec![
@@ -2670,6 +2705,28 @@ impl<'env, 'translator, 'module_translator> ExpTranslator<'env, 'translator, 'mo
                 cands.push(entry.clone().into())
             }
         }
+        cands
+    }
+
+    /// Translates a call, performing overload resolution. Reports an error if the function cannot be found.
+    /// This is used to resolve both calls to user functions and builtin operators.
+    fn translate_call(
+        &mut self,
+        loc: &Loc,
+        module: &Option<ModuleName>,
+        name: Symbol,
+        generics: Option<&[EA::Type]>,
+        args: &[&EA::Exp],
+        expected_type: &Type,
+        context: &ErrorMessageContext,
+    ) -> ExpData {
+        // Translate generic arguments, if any.
+        let generics = generics.as_ref().map(|ts| self.translate_types(ts));
+        // Translate arguments.
+        let (arg_types, mut translated_args) = self.translate_exp_list(args);
+        let args_have_errors = arg_types.iter().any(|t| t == &Type::Error);
+        // Lookup candidates.
+        let cands = self.call_candidates(module, name, arg_types.first());
         if cands.is_empty() {
             let display = self.display_call_target(module, name);
             self.error(loc, &format!("no function named `{}` found", display));
@@ -3065,7 +3122,10 @@ impl<'env, 'translator, 'module_translator> ExpTranslator<'env, 'translator, 'mo
         self.get_struct_with_diag(
             struct_name,
             struct_name_loc,
-            &format!("undeclared struct `{}`", struct_name.display(self.env())),
+            &format!(
+                "undeclared struct `{}`",
+                struct_name.display_full(self.env())
+            ),
         )
     }
 
@@ -8,26 +8,61 @@
 //! system, as well as type checking it and translating it to the spec language ast.
 
 use crate::{
-    ast::{Address, Attribute, ModuleName, Operation, QualifiedSymbol, Spec, Value},
+    ast::{
+        Address, Attribute, AttributeValue, Condition, ConditionKind, Exp, ExpData, ModuleName,
+        Operation, Pattern, QualifiedSymbol, Spec, Value,
+    },
     builder::builtins,
     intrinsics::IntrinsicDecl,
     model::{
-        FunId, FunctionKind, GlobalEnv, Loc, ModuleId, Parameter, QualifiedId, QualifiedInstId,
-        SpecFunId, SpecVarId, StructId, TypeParameter, TypeParameterKind,
+        FunId, FunctionKind, GlobalEnv, Loc, ModuleId, NodeId, Parameter, QualifiedId,
+        QualifiedInstId, SpecFunId, SpecVarId, StructId, TypeParameter, TypeParameterKind,
     },
+    options::ModelBuilderOptions,
     symbol::Symbol,
     ty::{
         gen_get_ty_param_kinds, infer_abilities, infer_and_check_abilities, is_phantom_type_arg,
-        Constraint, Type,
+        Constraint, PrimitiveType, ReferenceKind, Type, TypeArgError, TypeDisplayContext,
     },
 };
 use codespan_reporting::diagnostic::Severity;
 use itertools::Itertools;
-use move_binary_format::file_format::{AbilitySet, Visibility};
+use move_binary_format::file_format::{Ability, AbilitySet, Visibility};
 use move_compiler::{expansion::ast as EA, parser::ast as PA, shared::NumericalAddress};
 use move_core_types::account_address::AccountAddress;
+use num::{Signed, ToPrimitive, Zero};
+use petgraph::{algo::tarjan_scc, graphmap::DiGraphMap};
 use std::collections::{BTreeMap, BTreeSet};
 
+/// The binary operators which structs can overload via `#[operator]`.
+const OVERLOADABLE_OPS: [PA::BinOp_; 12] = [
+    PA::BinOp_::Add,
+    PA::BinOp_::Sub,
+    PA::BinOp_::Mul,
+    PA::BinOp_::Mod,
+    PA::BinOp_::Div,
+    PA::BinOp_::BitOr,
+    PA::BinOp_::BitAnd,
+    PA::BinOp_::Xor,
+    PA::BinOp_::Lt,
+    PA::BinOp_::Le,
+    PA::BinOp_::Gt,
+    PA::BinOp_::Ge,
+];
+
+/// The default value of `ModelBuilder::builtin_module_name`.
+const DEFAULT_BUILTIN_MODULE_NAME: &str = "$$";
+
+/// Stable codes of diagnostics reported by the builder, see `ModelBuilder::error_with_code`.
+pub(crate) mod error_codes {
+    /// A spec variable or schema is declared more than once in a module.
+    pub const DUPLICATE_DECLARATION: &str = "E0401";
+    /// A type refers to a struct which is not declared.
+    pub const UNDECLARED_TYPE: &str = "E0402";
+    /// A type argument lacks an ability required by the type parameter it instantiates.
+    pub const MISSING_ABILITY: &str = "E0403";
+}
+
 /// A builder is used to enter a sequence of modules in acyclic dependency order into the model. The
 /// builder maintains the incremental state of this process, such that the various tables
 /// are extended with each module translated. Each table is a mapping from fully qualified names
@@ -53,12 +88,69 @@ pub(crate) struct ModelBuilder<'env> {
     pub reverse_struct_table: BTreeMap<(ModuleId, StructId), QualifiedSymbol>,
     /// A symbol table for functions.
     pub fun_table: BTreeMap<QualifiedSymbol, FunEntry>,
+    /// A reverse mapping from function ids to the names of the functions in `fun_table`.
+    pub reverse_fun_table: BTreeMap<QualifiedId<FunId>, QualifiedSymbol>,
     /// A symbol table for constants.
     pub const_table: BTreeMap<QualifiedSymbol, ConstEntry>,
+    /// The constants referenced by expressions, indexed by the node of the constant's value
+    /// in the expression.
+    pub const_references: BTreeMap<NodeId, QualifiedSymbol>,
     /// A list of intrinsic declarations
     pub intrinsics: Vec<IntrinsicDecl>,
     /// A module lookup table from names to their ids.
     pub module_table: BTreeMap<ModuleName, ModuleId>,
+    /// The names of the packages the modules originate from, for modules with a known package.
+    pub module_packages: BTreeMap<ModuleId, Symbol>,
+    /// The packages of the target modules, i.e. of modules which do not come from a
+    /// dependency.
+    pub target_packages: BTreeSet<Symbol>,
+    /// An index from schemas to the functions whose specs include them, either directly or
+    /// via `apply`.
+    pub schema_applications: BTreeMap<QualifiedSymbol, BTreeSet<QualifiedId<FunId>>>,
+    /// Move functions overloading binary operators for structs, indexed by the struct and the
+    /// symbol of the operator, as registered via `#[operator]`.
+    pub operator_overloads: BTreeMap<(QualifiedId<StructId>, Symbol), QualifiedId<FunId>>,
+    /// Spec functions annotated with a `#[decreases]` termination hint.
+    pub decreasing_spec_funs: BTreeSet<QualifiedId<SpecFunId>>,
+    /// Structs which are mapped to intrinsic types and hence are opaque in specifications.
+    pub abstract_spec_types: BTreeSet<QualifiedId<StructId>>,
+    /// Whether struct definition ability checks are deferred until all modules are loaded.
+    pub defer_ability_checks: bool,
+    /// Structs whose definition ability checks have been deferred.
+    pub deferred_ability_checks: Vec<QualifiedSymbol>,
+    /// Whether the deferred ability checks have been run. Structs and functions defined after
+    /// this would escape the checks.
+    pub ability_checks_done: bool,
+    /// The schemas of known attributes, used to validate attributes, indexed by attribute name.
+    pub attribute_schemas: BTreeMap<String, AttributeSchema>,
+    /// The configuration determining which conditionally compiled declarations are entered
+    /// into the tables.
+    pub build_config: BuildConfig,
+    /// The depth up to which a generic struct may be nested in its own type arguments before
+    /// a note is generated, as in `A<A<A<u64>>>`, if any.
+    pub max_self_instantiation_depth: Option<usize>,
+    /// Whether the values of integer constants are validated against the range of their type.
+    pub check_const_ranges: bool,
+    /// The maximal number of type parameters a struct or function may declare, if any.
+    pub max_type_params: Option<usize>,
+    /// Whether exceeding `max_type_params` is an error rather than a note.
+    pub strict_max_type_params: bool,
+    /// Whether to generate notes about resources declared at unusual addresses.
+    pub check_resource_addresses: bool,
+    /// Whether to generate notes about struct fields in target modules whose type is an
+    /// integer wider than `u64`.
+    pub warn_wide_integer_fields: bool,
+    /// Whether to generate notes about declarations whose names differ only in case from
+    /// another declaration of the same kind in the same module.
+    pub warn_case_collisions: bool,
+    /// Whether to generate notes about directly recursive spec functions without a termination
+    /// hint.
+    pub warn_unproven_spec_fun_termination: bool,
+    /// The name of the pseudo module in which builtin functions and operators are declared.
+    pub builtin_module_name: Symbol,
+    /// Inclusive ranges of error codes reserved by a framework. Integer constants in target
+    /// modules whose value lies in one of these ranges generate a note.
+    pub reserved_error_code_ranges: Vec<(u64, u64)>,
 }
 
 /// A declaration of a specification function or operator in the builders state.
@@ -97,7 +189,6 @@ pub(crate) struct SpecVarEntry {
 #[derive(Debug)]
 pub(crate) struct SpecSchemaEntry {
     pub loc: Loc,
-    #[allow(dead_code)]
     pub name: QualifiedSymbol,
     pub module_id: ModuleId,
     pub type_params: Vec<TypeParameter>,
@@ -190,11 +281,84 @@ pub(crate) struct ConstEntry {
     pub ty: Type,
     pub value: Value,
     pub visibility: EntryVisibility,
+    pub attributes: Vec<Attribute>,
+}
+
+impl ConstEntry {
+    /// Determines whether the constant can be referenced in specifications (`in_spec`) or in
+    /// implementation code (`!in_spec`).
+    pub fn visible_in(&self, in_spec: bool) -> bool {
+        match self.visibility {
+            EntryVisibility::SpecAndImpl => true,
+            EntryVisibility::Spec => in_spec,
+            EntryVisibility::Impl => !in_spec,
+        }
+    }
+}
+
+/// The accessibility of a function as seen from a calling module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EffectiveVisibility {
+    /// The function can be called from the module.
+    Accessible,
+    /// The function can only be called from friends, and the module is not declared as one.
+    FriendOnly,
+    /// The function is private to another module.
+    Inaccessible,
+}
+
+/// Determines which conditionally compiled declarations are included in the model.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BuildConfig {
+    /// Whether to include declarations marked with `#[test]` or `#[test_only]`.
+    pub include_test: bool,
+    /// Whether to include declarations marked with `#[verify_only]`.
+    pub include_verify_only: bool,
+}
+
+impl Default for BuildConfig {
+    fn default() -> Self {
+        Self {
+            include_test: true,
+            include_verify_only: true,
+        }
+    }
+}
+
+impl BuildConfig {
+    /// Determines whether a declaration with the given attribute names is included.
+    pub fn includes<S: AsRef<str>>(&self, attr_names: impl IntoIterator<Item = S>) -> bool {
+        attr_names.into_iter().all(|name| match name.as_ref() {
+            "test" | "test_only" => self.include_test,
+            "verify_only" => self.include_verify_only,
+            _ => true,
+        })
+    }
+}
+
+/// The shape of the arguments accepted by a known attribute.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AttributeSchema {
+    /// Whether arbitrary arguments are accepted, as in `#[test(a = @0x1, b = @0x2)]`.
+    pub any_args: bool,
+    /// The names of the accepted arguments, as in `#[deprecated(note = b"...")]`.
+    pub args: BTreeSet<String>,
+    /// Whether the attribute can be assigned a value, as in `#[attr = value]`.
+    pub assignable: bool,
 }
 
 impl<'env> ModelBuilder<'env> {
     /// Creates a builders.
     pub fn new(env: &'env mut GlobalEnv) -> Self {
+        let options = env
+            .get_extension::<ModelBuilderOptions>()
+            .unwrap_or_default();
+        let builtin_module_name = env.symbol_pool().make(
+            options
+                .builtin_module_name
+                .as_deref()
+                .unwrap_or(DEFAULT_BUILTIN_MODULE_NAME),
+        );
         let mut translator = ModelBuilder {
             env,
             spec_fun_table: BTreeMap::new(),
@@ -204,14 +368,59 @@ impl<'env> ModelBuilder<'env> {
             struct_table: BTreeMap::new(),
             reverse_struct_table: BTreeMap::new(),
             fun_table: BTreeMap::new(),
+            reverse_fun_table: BTreeMap::new(),
             const_table: BTreeMap::new(),
+            const_references: BTreeMap::new(),
             intrinsics: Vec::new(),
             module_table: BTreeMap::new(),
+            module_packages: BTreeMap::new(),
+            target_packages: BTreeSet::new(),
+            schema_applications: BTreeMap::new(),
+            operator_overloads: BTreeMap::new(),
+            decreasing_spec_funs: BTreeSet::new(),
+            abstract_spec_types: BTreeSet::new(),
+            defer_ability_checks: options.defer_ability_checks,
+            deferred_ability_checks: vec![],
+            ability_checks_done: false,
+            attribute_schemas: BTreeMap::new(),
+            build_config: BuildConfig::default(),
+            max_self_instantiation_depth: options.max_self_instantiation_depth,
+            check_const_ranges: options.check_const_ranges,
+            max_type_params: options.max_type_params,
+            strict_max_type_params: options.strict_max_type_params,
+            check_resource_addresses: options.check_resource_addresses,
+            warn_wide_integer_fields: options.warn_wide_integer_fields,
+            warn_case_collisions: options.warn_case_collisions,
+            warn_unproven_spec_fun_termination: options.warn_unproven_spec_fun_termination,
+            builtin_module_name,
+            reserved_error_code_ranges: options.reserved_error_code_ranges.clone(),
         };
+        for name in ["test_only", "verify_only"] {
+            translator.register_attribute_schema(name, AttributeSchema::default());
+        }
+        for name in ["test", "expected_failure"] {
+            translator.register_attribute_schema(name, AttributeSchema {
+                any_args: true,
+                ..AttributeSchema::default()
+            });
+        }
+        translator.register_attribute_schema("operator", AttributeSchema {
+            assignable: true,
+            ..AttributeSchema::default()
+        });
+        translator.register_attribute_schema("deprecated", AttributeSchema {
+            args: ["note".to_owned()].into_iter().collect(),
+            ..AttributeSchema::default()
+        });
         builtins::declare_builtins(&mut translator);
         translator
     }
 
+    /// Registers the schema of an attribute, replacing any existing schema for the same name.
+    pub fn register_attribute_schema(&mut self, name: &str, schema: AttributeSchema) {
+        self.attribute_schemas.insert(name.to_owned(), schema);
+    }
+
     /// Shortcut for translating a Move AST location into ours.
     pub fn to_loc(&self, loc: &move_ir_types::location::Loc) -> Loc {
         self.env.to_loc(loc)
@@ -227,17 +436,346 @@ impl<'env> ModelBuilder<'env> {
         self.env.error_with_notes(at, msg, notes)
     }
 
+    /// Shortcut for reporting an error with one of the stable `error_codes`.
+    pub fn error_with_code(&self, at: &Loc, code: &str, msg: &str) {
+        self.env.error_with_code(at, code, msg)
+    }
+
+    /// Constructs a type display context used to visualize types in error messages.
+    pub fn type_display_context(&self) -> TypeDisplayContext<'_> {
+        TypeDisplayContext {
+            builder_struct_table: Some(&self.reverse_struct_table),
+            ..TypeDisplayContext::new(self.env)
+        }
+    }
+
+    /// Displays a type with the fully qualified names of structs, including their address, at
+    /// any nesting level. Unlike the display via `type_display_context`, names are never
+    /// abbreviated.
+    pub fn display_type_full(&self, ty: &Type) -> String {
+        let list = |tys: &[Type]| tys.iter().map(|ty| self.display_type_full(ty)).join(", ");
+        match ty {
+            Type::Struct(mid, sid, args) => {
+                let name = self
+                    .reverse_struct_table
+                    .get(&(*mid, *sid))
+                    .map(|name| name.display_full(self.env).to_string())
+                    .unwrap_or_else(|| ty.display(&self.type_display_context()).to_string());
+                if args.is_empty() {
+                    name
+                } else {
+                    format!("{}<{}>", name, list(args))
+                }
+            },
+            Type::Vector(elem) => format!("vector<{}>", self.display_type_full(elem)),
+            Type::Reference(ReferenceKind::Immutable, inner) => {
+                format!("&{}", self.display_type_full(inner))
+            },
+            Type::Reference(ReferenceKind::Mutable, inner) => {
+                format!("&mut {}", self.display_type_full(inner))
+            },
+            Type::Tuple(elems) => format!("({})", list(elems)),
+            Type::Fun(args, result) if result.is_unit() => {
+                format!("|{}|", self.display_type_full(args))
+            },
+            Type::Fun(args, result) => format!(
+                "|{}|{}",
+                self.display_type_full(args),
+                self.display_type_full(result)
+            ),
+            _ => ty.display(&self.type_display_context()).to_string(),
+        }
+    }
+
     /// Shortcut for a diagnosis note.
     pub fn note(&mut self, loc: &Loc, msg: &str) {
         self.env.diag(Severity::Note, loc, msg)
     }
 
+    /// Reports a duplicate declaration of `name` at `new_loc`, previously declared at `old_loc`.
+    fn report_duplicate_declaration(&self, name: &QualifiedSymbol, new_loc: &Loc, old_loc: &Loc) {
+        self.env.error_with_code_and_labels(
+            new_loc,
+            error_codes::DUPLICATE_DECLARATION,
+            &format!("duplicate declaration of `{}`", name.display(self.env)),
+            vec![(old_loc.clone(), "previously declared here".to_string())],
+        );
+    }
+
+    /// Checks that the type parameters of a declaration have distinct names, reporting an
+    /// error at each parameter which repeats the name of an earlier one.
+    pub fn check_distinct_type_params(&self, type_params: &[TypeParameter]) {
+        let mut seen = BTreeSet::new();
+        for TypeParameter(name, _, loc) in type_params {
+            if !seen.insert(*name) {
+                self.error(
+                    loc,
+                    &format!(
+                        "duplicate declaration of type parameter `{}`, \
+                        previously found in type parameters",
+                        name.display(self.env.symbol_pool())
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Generates a note if the spec function `oper`, whose translated body is `body`, calls
+    /// itself but has no `#[decreases]` termination hint, as the prover then cannot establish its
+    /// termination. Only reported if `warn_unproven_spec_fun_termination` is set.
+    pub fn check_spec_fun_termination(&self, name: &QualifiedSymbol, oper: &Operation, body: &Exp) {
+        let Operation::SpecFunction(module_id, fun_id, _) = oper else {
+            return;
+        };
+        if !self.warn_unproven_spec_fun_termination
+            || self
+                .decreasing_spec_funs
+                .contains(&module_id.qualified(*fun_id))
+        {
+            return;
+        }
+        let Some(entry) = self
+            .spec_fun_table
+            .get(name)
+            .and_then(|entries| entries.iter().find(|e| e.oper == *oper))
+        else {
+            return;
+        };
+        let mut is_recursive = false;
+        body.visit_pre_order(&mut |e| {
+            if let ExpData::Call(_, Operation::SpecFunction(mid, fid, _), _) = e {
+                is_recursive |= mid == module_id && fid == fun_id;
+            }
+            !is_recursive
+        });
+        if is_recursive {
+            self.env.diag(
+                Severity::Note,
+                &entry.loc,
+                &format!(
+                    "recursive spec function `{}` has no `decreases` annotation, so its \
+                     termination is not proven",
+                    name.display(self.env)
+                ),
+            );
+        }
+    }
+
+    /// Registers the Move function `fun_id` as overload of the binary operator `op` for the
+    /// struct `struct_id`, after checking that the operator can be overloaded and that the
+    /// function is declared in the module of the struct and takes two arguments of the struct
+    /// type. Overloads of comparison operators must return `bool`. The overload is declared
+    /// under the symbol of the operator in the module of the struct, where operator resolution
+    /// finds it for operands of the struct type, see `resolve_op_module`. Overloads are only
+    /// visible in specifications, so operators in Move code keep their meaning in all pipelines.
+    pub fn register_operator_overload(
+        &mut self,
+        op: PA::BinOp_,
+        struct_id: QualifiedId<StructId>,
+        fun_id: QualifiedId<FunId>,
+    ) {
+        let Some(entry) = self
+            .reverse_fun_table
+            .get(&fun_id)
+            .and_then(|name| self.fun_table.get(name))
+            .cloned()
+        else {
+            return;
+        };
+        let struct_name = self.reverse_struct_table[&(struct_id.module_id, struct_id.id)].clone();
+        let fun_name = fun_id
+            .id
+            .symbol()
+            .display(self.env.symbol_pool())
+            .to_string();
+        if !OVERLOADABLE_OPS.contains(&op) {
+            self.error(
+                &entry.loc,
+                &format!("operator `{}` cannot be overloaded", op.symbol()),
+            );
+            return;
+        }
+        let is_struct_param = |Parameter(_, ty, _): &Parameter| matches!(ty, Type::Struct(mid, sid, _) if mid.qualified(*sid) == struct_id);
+        if fun_id.module_id != struct_id.module_id
+            || entry.params.len() != 2
+            || !entry.params.iter().all(is_struct_param)
+        {
+            self.error(
+                &entry.loc,
+                &format!(
+                    "function `{}` overloading `{}` must be declared in the module of `{}` and \
+                     take two arguments of this type",
+                    fun_name,
+                    op.symbol(),
+                    struct_name.display(self.env)
+                ),
+            );
+            return;
+        }
+        if matches!(
+            op,
+            PA::BinOp_::Lt | PA::BinOp_::Le | PA::BinOp_::Gt | PA::BinOp_::Ge
+        ) && entry.result_type != Type::new_prim(PrimitiveType::Bool)
+        {
+            self.error(
+                &entry.loc,
+                &format!(
+                    "function `{}` overloading `{}` must return `bool`",
+                    fun_name,
+                    op.symbol()
+                ),
+            );
+            return;
+        }
+        let op_symbol = self.env.symbol_pool().make(op.symbol());
+        if self
+            .operator_overloads
+            .insert((struct_id, op_symbol), fun_id)
+            .is_some()
+        {
+            self.error(
+                &entry.loc,
+                &format!(
+                    "duplicate overload of `{}` for `{}`",
+                    op.symbol(),
+                    struct_name.display(self.env)
+                ),
+            );
+            return;
+        }
+        let overload_name = QualifiedSymbol {
+            module_name: struct_name.module_name,
+            symbol: op_symbol,
+        };
+        self.define_spec_or_builtin_fun(overload_name, SpecOrBuiltinFunEntry {
+            loc: entry.loc,
+            oper: Operation::MoveFunction(fun_id.module_id, fun_id.id),
+            type_params: entry.type_params,
+            type_param_constraints: BTreeMap::new(),
+            params: entry.params,
+            result_type: entry.result_type,
+            visibility: EntryVisibility::Spec,
+        });
+    }
+
+    /// Registers the function `name` as operator overload if it is declared with an attribute
+    /// like `#[operator = b"+"]`. The overloaded struct is the type of its first parameter.
+    fn register_operator_attribute(&mut self, name: &QualifiedSymbol) {
+        let Some(entry) = self.fun_table.get(name) else {
+            return;
+        };
+        let operator = self.env.symbol_pool().make("operator");
+        let Some(attr) = entry.attributes.iter().find(|attr| attr.name() == operator) else {
+            return;
+        };
+        let loc = self.env.get_node_loc(attr.node_id());
+        let op = match attr {
+            Attribute::Assign(_, _, AttributeValue::Value(_, Value::ByteArray(bytes))) => {
+                OVERLOADABLE_OPS
+                    .iter()
+                    .find(|op| op.symbol().as_bytes() == bytes.as_slice())
+                    .copied()
+            },
+            _ => None,
+        };
+        let Some(op) = op else {
+            self.error(
+                &loc,
+                "expected `#[operator = b\"<op>\"]` with an overloadable binary operator",
+            );
+            return;
+        };
+        let fun_id = entry.module_id.qualified(entry.fun_id);
+        match entry.params.first() {
+            Some(Parameter(_, Type::Struct(mid, sid, _), _)) => {
+                let struct_id = mid.qualified(*sid);
+                self.register_operator_overload(op, struct_id, fun_id)
+            },
+            _ => self.error(
+                &entry.loc,
+                &format!(
+                    "function `{}` overloading `{}` must take arguments of a struct type",
+                    name.display(self.env),
+                    op.symbol()
+                ),
+            ),
+        }
+    }
+
+    /// Reports a note, or an error if `strict_max_type_params` is set, if the declaration
+    /// `name` has more type parameters than `max_type_params` allows.
+    fn check_type_param_count(
+        &self,
+        loc: &Loc,
+        name: &QualifiedSymbol,
+        type_params: &[TypeParameter],
+    ) {
+        if let Some(max) = self.max_type_params {
+            if type_params.len() > max {
+                let severity = if self.strict_max_type_params {
+                    Severity::Error
+                } else {
+                    Severity::Note
+                };
+                self.env.diag(
+                    severity,
+                    loc,
+                    &format!(
+                        "`{}` declares {} type parameters, which exceeds the maximum of {}",
+                        name.display(self.env),
+                        type_params.len(),
+                        max
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Generates a note if the function, declared in a target package, has the simple name of
+    /// a builtin function, as `len`, but none of the builtin overloads takes the same number of
+    /// arguments. Calls of such a function can be confused with calls of the builtin.
+    fn check_builtin_name_arity(&self, name: &QualifiedSymbol, entry: &FunEntry) {
+        if !self.in_target_package(entry.module_id, &entry.loc) {
+            return;
+        }
+        let builtin_name = QualifiedSymbol {
+            module_name: self.builtin_module(),
+            symbol: name.symbol,
+        };
+        let Some(builtins) = self.spec_fun_table.get(&builtin_name) else {
+            return;
+        };
+        if builtins.is_empty()
+            || builtins
+                .iter()
+                .any(|builtin| builtin.params.len() == entry.params.len())
+        {
+            return;
+        }
+        self.env.diag(
+            Severity::Note,
+            &entry.name_loc,
+            &format!(
+                "function `{}` has the name of a builtin function but takes {} arguments \
+                 instead of {}",
+                name.display(self.env),
+                entry.params.len(),
+                builtins
+                    .iter()
+                    .map(|builtin| builtin.params.len())
+                    .unique()
+                    .join(" or ")
+            ),
+        );
+    }
+
     /// Defines a spec function, adding it to the spec fun table.
     pub fn define_spec_or_builtin_fun(
         &mut self,
         name: QualifiedSymbol,
         entry: SpecOrBuiltinFunEntry,
     ) {
+        self.check_distinct_type_params(&entry.type_params);
         if self.fun_table.contains_key(&name) {
             self.env.error(
                 &entry.loc,
@@ -247,6 +785,28 @@ impl<'env> ModelBuilder<'env> {
                 ),
             );
         }
+        if entry.visibility == EntryVisibility::SpecAndImpl {
+            // The entry is visible in code as well, so its signature must be representable there.
+            let display_context = self.type_display_context();
+            for ty in entry
+                .params
+                .iter()
+                .map(|Parameter(_, ty, _)| ty)
+                .chain(std::iter::once(&entry.result_type))
+            {
+                if ty.is_spec() {
+                    self.env.error(
+                        &entry.loc,
+                        &format!(
+                            "function `{}` is visible in code but its signature uses the \
+                             specification-only type `{}`",
+                            name.symbol.display(self.env.symbol_pool()),
+                            ty.display(&display_context)
+                        ),
+                    );
+                }
+            }
+        }
         // TODO: check whether overloads are distinguishable
         self.spec_fun_table.entry(name).or_default().push(entry);
     }
@@ -261,6 +821,7 @@ impl<'env> ModelBuilder<'env> {
         type_params: Vec<TypeParameter>,
         type_: Type,
     ) {
+        self.check_distinct_type_params(&type_params);
         let entry = SpecVarEntry {
             loc: loc.clone(),
             module_id,
@@ -269,9 +830,7 @@ impl<'env> ModelBuilder<'env> {
             type_,
         };
         if let Some(old) = self.spec_var_table.insert(name.clone(), entry) {
-            let var_name = name.display(self.env);
-            self.error(loc, &format!("duplicate declaration of `{}`", var_name));
-            self.note(&old.loc, &format!("previous declaration of `{}`", var_name));
+            self.report_duplicate_declaration(&name, loc, &old.loc);
         }
     }
 
@@ -284,6 +843,7 @@ impl<'env> ModelBuilder<'env> {
         type_params: Vec<TypeParameter>,
         vars: Vec<Parameter>,
     ) {
+        self.check_distinct_type_params(&type_params);
         let entry = SpecSchemaEntry {
             loc: loc.clone(),
             name: name.clone(),
@@ -295,19 +855,37 @@ impl<'env> ModelBuilder<'env> {
             included_spec: Spec::default(),
         };
         if let Some(old) = self.spec_schema_table.insert(name.clone(), entry) {
-            let schema_display = name.display(self.env);
-            self.error(
-                loc,
-                &format!("duplicate declaration of `{}`", schema_display),
-            );
-            self.error(
-                &old.loc,
-                &format!("previous declaration of `{}`", schema_display),
-            );
+            self.report_duplicate_declaration(&name, loc, &old.loc);
         }
         self.unused_schema_set.insert(name);
     }
 
+    /// Records that the spec of the function `fun_name` includes the schema `schema_name`.
+    pub fn record_schema_application(
+        &mut self,
+        schema_name: QualifiedSymbol,
+        fun_name: &QualifiedSymbol,
+    ) {
+        if let Some(entry) = self.fun_table.get(fun_name) {
+            let fun_id = entry.module_id.qualified(entry.fun_id);
+            self.schema_applications
+                .entry(schema_name)
+                .or_default()
+                .insert(fun_id);
+        }
+    }
+
+    /// Returns the functions whose specs include the given schema.
+    pub fn schema_applications(
+        &self,
+        schema_name: &QualifiedSymbol,
+    ) -> BTreeSet<QualifiedId<FunId>> {
+        self.schema_applications
+            .get(schema_name)
+            .cloned()
+            .unwrap_or_default()
+    }
+
     /// Defines a struct type.
     pub fn define_struct(
         &mut self,
@@ -320,8 +898,11 @@ impl<'env> ModelBuilder<'env> {
         type_params: Vec<TypeParameter>,
         fields: Option<BTreeMap<Symbol, (Loc, usize, Type)>>,
     ) {
+        self.check_not_defined_after_ability_checks(&name, &loc);
+        self.check_distinct_type_params(&type_params);
+        self.check_type_param_count(&loc, &name, &type_params);
         let entry = StructEntry {
-            loc,
+            loc: loc.clone(),
             attributes,
             module_id,
             struct_id,
@@ -329,21 +910,244 @@ impl<'env> ModelBuilder<'env> {
             type_params,
             fields,
         };
+        if self.check_resource_addresses && abilities.has_key() {
+            self.check_resource_declaration(&name, &entry);
+        }
+        if self.warn_case_collisions {
+            self.check_case_collision(
+                "struct",
+                &name,
+                &loc,
+                self.struct_table
+                    .iter()
+                    .map(|(name, entry)| (name, &entry.loc)),
+            );
+        }
         self.struct_table.insert(name.clone(), entry);
         self.reverse_struct_table
             .insert((module_id, struct_id), name);
     }
 
-    /// Defines a function.
-    pub fn define_fun(&mut self, name: QualifiedSymbol, entry: FunEntry) {
-        self.fun_table.insert(name, entry);
+    /// Reports a bug if `name` is defined after the deferred ability checks have run, since the
+    /// definition then escapes these checks. In debug builds, this also fails an assertion.
+    fn check_not_defined_after_ability_checks(&self, name: &QualifiedSymbol, loc: &Loc) {
+        if self.defer_ability_checks && self.ability_checks_done {
+            self.env.diag(
+                Severity::Bug,
+                loc,
+                &format!(
+                    "`{}` is defined after the deferred ability checks have run",
+                    name.display(self.env)
+                ),
+            );
+            debug_assert!(
+                false,
+                "`{}` is defined after the deferred ability checks have run",
+                name.display(self.env)
+            );
+        }
+    }
+
+    /// Generates a note if the resource `name` is declared in the builtin module or in a module
+    /// at the zero address, which no account can hold resources of.
+    fn check_resource_declaration(&self, name: &QualifiedSymbol, entry: &StructEntry) {
+        let addr = name.module_name.addr();
+        if name.module_name == self.builtin_module()
+            || *addr == Address::Numerical(AccountAddress::ZERO)
+        {
+            self.env.diag(
+                Severity::Note,
+                &entry.loc,
+                &format!(
+                    "resource `{}` is declared at the unusual address `{}`",
+                    name.display(self.env),
+                    self.env.display(addr)
+                ),
+            );
+        }
+    }
+
+    /// Defines a function. `declared_result_arity` is the number of return values declared in
+    /// the source; an internal error is reported if the result type of the entry disagrees.
+    pub fn define_fun(
+        &mut self,
+        name: QualifiedSymbol,
+        entry: FunEntry,
+        declared_result_arity: usize,
+    ) {
+        let result_arity = match &entry.result_type {
+            Type::Tuple(tys) => Some(tys.len()),
+            Type::Error => None,
+            _ => Some(1),
+        };
+        if result_arity.map_or(false, |arity| arity != declared_result_arity) {
+            self.env.diag(
+                Severity::Bug,
+                &entry.loc,
+                &format!(
+                    "result type of `{}` has {} values but {} are declared",
+                    name.display(self.env),
+                    result_arity.unwrap_or_default(),
+                    declared_result_arity
+                ),
+            );
+        }
+        self.check_not_defined_after_ability_checks(&name, &entry.loc);
+        self.check_distinct_type_params(&entry.type_params);
+        self.check_type_param_count(&entry.loc, &name, &entry.type_params);
+        self.check_builtin_name_arity(&name, &entry);
+        if self.warn_case_collisions {
+            self.check_case_collision(
+                "function",
+                &name,
+                &entry.name_loc,
+                self.fun_table
+                    .iter()
+                    .map(|(name, entry)| (name, &entry.name_loc)),
+            );
+        }
+        let fun_id = entry.module_id.qualified(entry.fun_id);
+        if self.fun_table.insert(name.clone(), entry).is_none() {
+            self.reverse_fun_table.insert(fun_id, name.clone());
+        }
+        self.register_operator_attribute(&name);
     }
 
     /// Defines a constant.
     pub fn define_const(&mut self, name: QualifiedSymbol, entry: ConstEntry) {
+        if self.warn_case_collisions {
+            self.check_case_collision(
+                "constant",
+                &name,
+                &entry.loc,
+                self.const_table
+                    .iter()
+                    .map(|(name, entry)| (name, &entry.loc)),
+            );
+        }
         self.const_table.insert(name, entry);
     }
 
+    /// Generates a note if the declaration `name` of the given kind has a name which differs only
+    /// in case from one of the `existing` declarations of the same kind in the same module.
+    fn check_case_collision<'a>(
+        &self,
+        kind: &str,
+        name: &QualifiedSymbol,
+        loc: &Loc,
+        existing: impl Iterator<Item = (&'a QualifiedSymbol, &'a Loc)>,
+    ) {
+        let pool = self.env.symbol_pool();
+        let lower_name = pool.string(name.symbol).to_lowercase();
+        for (other, other_loc) in existing {
+            if other.module_name == name.module_name
+                && other.symbol != name.symbol
+                && pool.string(other.symbol).to_lowercase() == lower_name
+            {
+                self.env.diag_with_labels(
+                    Severity::Note,
+                    loc,
+                    &format!(
+                        "{} `{}` differs only in case from `{}`",
+                        kind,
+                        name.display(self.env),
+                        other.display(self.env)
+                    ),
+                    vec![(
+                        other_loc.clone(),
+                        format!("`{}` declared here", other.display(self.env)),
+                    )],
+                );
+            }
+        }
+    }
+
+    /// Generates a note if the integer constant `name` of the given module is declared in a
+    /// target package and its value lies in one of the `reserved_error_code_ranges`. This must
+    /// be called once the value of the constant is known.
+    pub fn check_reserved_error_code(
+        &self,
+        module_id: ModuleId,
+        name: &QualifiedSymbol,
+        entry: &ConstEntry,
+    ) {
+        if self.reserved_error_code_ranges.is_empty()
+            || !entry.ty.is_number()
+            || !self.in_target_package(module_id, &entry.loc)
+        {
+            return;
+        }
+        let Value::Number(n) = &entry.value else {
+            return;
+        };
+        let Some(code) = n.to_u64() else {
+            return;
+        };
+        if let Some((low, high)) = self
+            .reserved_error_code_ranges
+            .iter()
+            .find(|(low, high)| (*low..=*high).contains(&code))
+        {
+            self.env.diag(
+                Severity::Note,
+                &entry.loc,
+                &format!(
+                    "value {} of constant `{}` lies in the reserved error code range {}..={}",
+                    code,
+                    name.display(self.env),
+                    low,
+                    high
+                ),
+            );
+        }
+    }
+
+    /// If `check_const_ranges` is set, checks that the integers in `value` are within the range
+    /// of their type `ty`. If not, an error is reported at `loc` and `Value::Error` is returned
+    /// as a placeholder.
+    pub fn validated_const_value(&self, loc: &Loc, ty: &Type, value: Value) -> Value {
+        fn in_range(ty: &Type, value: &Value) -> Result<(), PrimitiveType> {
+            match (ty, value) {
+                (Type::Primitive(prim), Value::Number(n)) if ty.is_number() => {
+                    match prim.get_max_value() {
+                        Some(max) if n.is_negative() || *n > max => Err(*prim),
+                        _ => Ok(()),
+                    }
+                },
+                (Type::Vector(elem_ty), Value::Vector(elems)) => {
+                    elems.iter().try_for_each(|elem| in_range(elem_ty, elem))
+                },
+                _ => Ok(()),
+            }
+        }
+        if !self.check_const_ranges {
+            return value;
+        }
+        match in_range(ty, &value) {
+            Ok(()) => value,
+            Err(prim) => {
+                self.error(
+                    loc,
+                    &format!(
+                        "constant value out of range for type `{}`",
+                        Type::new_prim(prim).display(&self.type_display_context())
+                    ),
+                );
+                Value::Error
+            },
+        }
+    }
+
+    /// Returns all constants declared so far whose type is (structurally) equal to `ty`,
+    /// e.g. all `u64` error codes or all `u8` constants modeling an enumeration.
+    pub fn consts_of_type(&self, ty: &Type) -> Vec<(&QualifiedSymbol, &Value)> {
+        self.const_table
+            .iter()
+            .filter(|(_, entry)| entry.ty == *ty)
+            .map(|(name, entry)| (name, &entry.value))
+            .collect()
+    }
+
     pub fn resolve_address(&self, loc: &Loc, addr: &EA::Address) -> NumericalAddress {
         match addr {
             EA::Address::Numerical(_, bytes) => bytes.value,
@@ -354,41 +1158,116 @@ impl<'env> ModelBuilder<'env> {
         }
     }
 
+    /// Returns the canonical form of an address, as used in the keys of the builder's tables.
+    /// A symbolic address with a known value is replaced by that value. Numerical addresses
+    /// are already canonical, since they are stored with a fixed width, so `0x1` and
+    /// `0x00..01` are the same address.
+    pub fn canonicalize_address(&self, addr: &Address) -> Address {
+        match addr {
+            Address::Symbolic(alias) => self
+                .env
+                .resolve_address_alias(*alias)
+                .map(Address::Numerical)
+                .unwrap_or_else(|| addr.clone()),
+            Address::Numerical(_) => addr.clone(),
+        }
+    }
+
     /// Looks up a type (struct), reporting an error if it is not found.
     pub fn lookup_type(&self, loc: &Loc, name: &QualifiedSymbol) -> Type {
-        self.struct_table
-            .get(name)
-            .cloned()
-            .map(|e| {
-                Type::Struct(
-                    e.module_id,
-                    e.struct_id,
-                    TypeParameter::vec_to_formals(&e.type_params),
-                )
-            })
-            .unwrap_or_else(|| {
-                self.error(
-                    loc,
-                    &format!("undeclared `{}`", name.display_full(self.env)),
-                );
-                Type::Error
-            })
+        self.try_lookup_type(name).unwrap_or_else(|| {
+            self.error_with_code(
+                loc,
+                error_codes::UNDECLARED_TYPE,
+                &format!("undeclared `{}`", name.display_full(self.env)),
+            );
+            Type::Error
+        })
+    }
+
+    /// Looks up a type (struct), returning `None` without reporting an error if it is not
+    /// found. This can be used for speculative resolution.
+    pub fn try_lookup_type(&self, name: &QualifiedSymbol) -> Option<Type> {
+        self.struct_table.get(name).map(|e| {
+            Type::Struct(
+                e.module_id,
+                e.struct_id,
+                TypeParameter::vec_to_formals(&e.type_params),
+            )
+        })
     }
 
-    /// Looks up the fields of a structure, with instantiated field types.
-    pub fn lookup_struct_fields(&self, id: QualifiedInstId<StructId>) -> BTreeMap<Symbol, Type> {
+    /// Looks up the fields of a structure, with instantiated field types. If the instantiation
+    /// is empty, the field types are returned uninstantiated. If the instantiation does not
+    /// match the number of type parameters of the struct, an error is reported at `loc` and
+    /// all field types are `Type::Error`.
+    pub fn lookup_struct_fields(
+        &self,
+        id: QualifiedInstId<StructId>,
+        loc: &Loc,
+    ) -> BTreeMap<Symbol, Type> {
         let entry = self.lookup_struct_entry(id.to_qualified_id());
+        let arity_ok = id.inst.is_empty() || id.inst.len() == entry.type_params.len();
+        if !arity_ok {
+            self.error(
+                loc,
+                &format!(
+                    "wrong number of type arguments for struct `{}`: expected {}, found {}",
+                    self.reverse_struct_table[&(id.module_id, id.id)].display(self.env),
+                    entry.type_params.len(),
+                    id.inst.len()
+                ),
+            );
+        }
         entry
             .fields
             .as_ref()
             .map(|f| {
                 f.iter()
-                    .map(|(n, (_, _, field_ty))| (*n, field_ty.instantiate(&id.inst)))
+                    .map(|(n, (_, _, field_ty))| {
+                        let ty = if !arity_ok {
+                            Type::Error
+                        } else if id.inst.is_empty() {
+                            field_ty.clone()
+                        } else {
+                            self.instantiate_checked(field_ty, &id.inst, loc)
+                        };
+                        (*n, ty)
+                    })
                     .collect::<BTreeMap<_, _>>()
             })
             .unwrap_or_default()
     }
 
+    /// Instantiates the type parameters in `ty` with `inst`. If `inst` does not cover all type
+    /// parameters occurring in `ty`, an error is reported at `loc` and `Type::Error` is
+    /// returned. `Type::Error` is also returned if `inst` contains an error type, assuming
+    /// that the error has been reported already.
+    pub fn instantiate_checked(&self, ty: &Type, inst: &[Type], loc: &Loc) -> Type {
+        let mut max_param = None;
+        ty.visit(&mut |t| {
+            if let Type::TypeParameter(idx) = t {
+                max_param = max_param.max(Some(*idx as usize));
+            }
+        });
+        match max_param {
+            Some(idx) if idx >= inst.len() => {
+                self.error(
+                    loc,
+                    &format!(
+                        "type instantiation with {} type arguments does not cover \
+                         type parameter #{}",
+                        inst.len(),
+                        idx
+                    ),
+                );
+                Type::Error
+            },
+            _ if inst.iter().any(|t| t.is_error()) => Type::Error,
+            _ => ty.instantiate(inst),
+        }
+    }
+
     /// Looks up the abilities of a struct.
     /// TODO(#12437): get rid of this once we have new UnificationContext
     pub fn lookup_struct_abilities(&self, id: QualifiedId<StructId>) -> AbilitySet {
@@ -396,6 +1275,21 @@ impl<'env> ModelBuilder<'env> {
         entry.abilities
     }
 
+    /// Returns the names of the given abilities in canonical order, i.e. the subset of
+    /// `["copy", "drop", "store", "key"]` which is present.
+    pub fn ability_names(&self, abilities: AbilitySet) -> Vec<&'static str> {
+        [
+            (Ability::Copy, "copy"),
+            (Ability::Drop, "drop"),
+            (Ability::Store, "store"),
+            (Ability::Key, "key"),
+        ]
+        .into_iter()
+        .filter(|(ability, _)| abilities.has_ability(*ability))
+        .map(|(_, name)| name)
+        .collect()
+    }
+
     /// Get all the structs which have been build so far.
     pub fn get_struct_ids(&self) -> impl Iterator<Item = QualifiedId<StructId>> + '_ {
         self.struct_table
@@ -403,17 +1297,279 @@ impl<'env> ModelBuilder<'env> {
             .map(|e| e.module_id.qualified(e.struct_id))
     }
 
-    /// Looks up the StructEntry for a qualified id.
-    pub fn lookup_struct_entry(&self, id: QualifiedId<StructId>) -> &StructEntry {
-        let struct_name = self
-            .reverse_struct_table
-            .get(&(id.module_id, id.id))
+    /// Returns the symbols declared in other modules which are referenced by the given module,
+    /// i.e. the minimal set of imports needed by it. This considers the types in struct fields,
+    /// function and spec function signatures, and constants, as well as the types, functions,
+    /// and spec functions used in function bodies and specifications which have been added to
+    /// the environment.
+    pub fn external_references(&self, module_id: ModuleId) -> BTreeSet<QualifiedSymbol> {
+        let mut types = vec![];
+        for entry in self.struct_table.values() {
+            if entry.module_id == module_id {
+                types.extend(
+                    entry
+                        .fields
+                        .iter()
+                        .flatten()
+                        .map(|(_, (_, _, ty))| ty.clone()),
+                )
+            }
+        }
+        for entry in self.fun_table.values() {
+            if entry.module_id == module_id {
+                types.extend(entry.params.iter().map(|Parameter(_, ty, _)| ty.clone()));
+                types.push(entry.result_type.clone());
+            }
+        }
+        for entry in self.spec_fun_table.values().flatten() {
+            if matches!(entry.oper, Operation::SpecFunction(mid, ..) if mid == module_id) {
+                types.extend(entry.params.iter().map(|Parameter(_, ty, _)| ty.clone()));
+                types.push(entry.result_type.clone());
+            }
+        }
+        let mut exps = vec![];
+        if let Some(data) = self.env.module_data.get(module_id.to_usize()) {
+            types.extend(
+                self.const_table
+                    .iter()
+                    .filter(|(name, _)| name.module_name == data.name)
+                    .map(|(_, entry)| entry.ty.clone()),
+            );
+            let add_spec = |spec: &Spec, exps: &mut Vec<Exp>| {
+                for cond in spec
+                    .conditions
+                    .iter()
+                    .chain(spec.update_map.values())
+                    .chain(spec.on_impl.values().flat_map(|s| s.conditions.iter()))
+                {
+                    exps.push(cond.exp.clone());
+                    exps.extend(cond.additional_exps.iter().cloned());
+                }
+            };
+            add_spec(&data.module_spec.borrow(), &mut exps);
+            for struct_data in data.struct_data.values() {
+                add_spec(&struct_data.spec.borrow(), &mut exps);
+            }
+            for fun_data in data.function_data.values() {
+                add_spec(&fun_data.spec.borrow(), &mut exps);
+                exps.extend(fun_data.def.iter().cloned());
+            }
+            exps.extend(
+                data.spec_funs
+                    .values()
+                    .flat_map(|decl| decl.body.iter().cloned()),
+            );
+        }
+
+        let mut refs = BTreeSet::new();
+        for exp in &exps {
+            for node_id in exp.node_ids() {
+                types.push(self.env.get_node_type(node_id));
+                types.extend(self.env.get_node_instantiation(node_id));
+            }
+            for fun in exp.called_funs() {
+                if fun.module_id != module_id {
+                    refs.insert(QualifiedSymbol {
+                        module_name: self.env.module_data[fun.module_id.to_usize()].name.clone(),
+                        symbol: fun.id.symbol(),
+                    });
+                }
+            }
+            for fun in exp.called_spec_funs(self.env) {
+                if fun.module_id != module_id {
+                    let data = &self.env.module_data[fun.module_id.to_usize()];
+                    refs.insert(QualifiedSymbol {
+                        module_name: data.name.clone(),
+                        symbol: data.spec_funs[&fun.id].name,
+                    });
+                }
+            }
+        }
+        for ty in &types {
+            ty.visit(&mut |t| {
+                if let Type::Struct(mid, sid, _) = t {
+                    if *mid != module_id {
+                        if let Some(name) = self.reverse_struct_table.get(&(*mid, *sid)) {
+                            refs.insert(name.clone());
+                        }
+                    }
+                }
+            });
+        }
+        refs
+    }
+
+    /// Returns the modules imported by `use` declarations of the given module which are not
+    /// referenced by it, according to `external_references`. The names are returned as given
+    /// in the declarations. This requires the module to have been added to the environment.
+    pub fn unused_imports(&self, module_id: ModuleId) -> Vec<ModuleName> {
+        let Some(data) = self.env.module_data.get(module_id.to_usize()) else {
+            return vec![];
+        };
+        let referenced = self
+            .external_references(module_id)
+            .into_iter()
+            .filter_map(|name| {
+                self.env
+                    .find_module(&name.module_name)
+                    .map(|module| module.get_id())
+            })
+            .collect::<BTreeSet<_>>();
+        let mut unused = vec![];
+        for use_decl in &data.use_decls {
+            if let Some(mid) = use_decl.module_id {
+                if mid != module_id
+                    && !referenced.contains(&mid)
+                    && !unused.contains(&use_decl.module_name)
+                {
+                    unused.push(use_decl.module_name.clone());
+                }
+            }
+        }
+        unused
+    }
+
+    /// Generates notes about `use` declarations in target modules which import a module that
+    /// is never referenced. Declarations for which the compiler already reported a diagnostic,
+    /// namely its warning about unused aliases, are skipped. Function bodies are only available
+    /// if the model is built with `ModelBuilderOptions::compile_via_model`, hence this is only
+    /// run in that case, as otherwise modules used only in code would be reported.
+    pub fn warn_unused_imports(&self) {
+        for module in self.env.get_modules() {
+            if !module.is_target() {
+                continue;
+            }
+            let unused = self.unused_imports(module.get_id());
+            for use_decl in module.get_use_decls() {
+                if use_decl.module_id.is_some()
+                    && unused.contains(&use_decl.module_name)
+                    && !self.env.has_diag_at(&use_decl.loc)
+                {
+                    self.env.diag(
+                        Severity::Note,
+                        &use_decl.loc,
+                        &format!(
+                            "module `{}` is imported but never used",
+                            use_decl.module_name.display_full(self.env)
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Looks up the StructEntry for a qualified id.
+    pub fn lookup_struct_entry(&self, id: QualifiedId<StructId>) -> &StructEntry {
+        let struct_name = self
+            .reverse_struct_table
+            .get(&(id.module_id, id.id))
             .expect("invalid Type::Struct");
         self.struct_table
             .get(struct_name)
             .expect("invalid Type::Struct")
     }
 
+    /// Builds the call graph of the functions defined so far, mapping each function to the
+    /// Move functions called from its body, including via closures. Only functions whose
+    /// bodies have been added to the environment, i.e. when compiling via the model, have
+    /// outgoing edges.
+    pub fn build_call_graph(&self) -> BTreeMap<QualifiedId<FunId>, BTreeSet<QualifiedId<FunId>>> {
+        self.fun_table
+            .values()
+            .map(|entry| {
+                let callees = self
+                    .env
+                    .module_data
+                    .get(entry.module_id.to_usize())
+                    .and_then(|data| data.function_data.get(&entry.fun_id))
+                    .and_then(|data| data.def.as_ref())
+                    .map(|def| def.called_funs())
+                    .unwrap_or_default();
+                (entry.module_id.qualified(entry.fun_id), callees)
+            })
+            .collect()
+    }
+
+    /// Computes the functions which are part of a cycle in the call graph, i.e. which call
+    /// themselves directly or indirectly. The call graph is built once and its strongly
+    /// connected components determine the cycles.
+    pub fn recursive_functions(&self) -> BTreeSet<QualifiedId<FunId>> {
+        let call_graph = self.build_call_graph();
+        let mut graph = DiGraphMap::<QualifiedId<FunId>, ()>::new();
+        for (caller, callees) in &call_graph {
+            graph.add_node(*caller);
+            for callee in callees {
+                graph.add_edge(*caller, *callee, ());
+            }
+        }
+        tarjan_scc(&graph)
+            .into_iter()
+            .filter(|scc| scc.len() > 1 || graph.contains_edge(scc[0], scc[0]))
+            .flatten()
+            .collect()
+    }
+
+    /// Generates notes about functions in target modules which call themselves, directly or
+    /// via other functions, see `recursive_functions`. As the call graph is built from function
+    /// bodies, this never reports anything unless compiling via the model.
+    pub fn warn_recursive_functions(&self) {
+        let recursive = self.recursive_functions();
+        for (name, entry) in &self.fun_table {
+            if self.env.get_module(entry.module_id).is_target()
+                && recursive.contains(&entry.module_id.qualified(entry.fun_id))
+            {
+                self.env.diag(
+                    Severity::Note,
+                    &entry.name_loc,
+                    &format!("function `{}` is recursive", name.display(self.env)),
+                );
+            }
+        }
+    }
+
+    /// Determines whether values of the given struct can be obtained outside of its module,
+    /// i.e. whether any public function returns it by value, possibly as part of a tuple,
+    /// vector, or struct instantiation. Structs for which this is not the case are opaque
+    /// handles which can only be constructed internally.
+    pub fn struct_has_public_constructor(&self, id: QualifiedId<StructId>) -> bool {
+        self.fun_table.values().any(|entry| {
+            entry.visibility == Visibility::Public && Self::returns_struct(&entry.result_type, id)
+        })
+    }
+
+    /// Determines whether a value of the given result type gives access to a value of the
+    /// struct `id`, possibly as part of a tuple, vector, or struct instantiation.
+    fn returns_struct(ty: &Type, id: QualifiedId<StructId>) -> bool {
+        match ty {
+            Type::Struct(mid, sid, args) => {
+                mid.qualified(*sid) == id || args.iter().any(|arg| Self::returns_struct(arg, id))
+            },
+            Type::Vector(elem) => Self::returns_struct(elem, id),
+            Type::Tuple(elems) => elems.iter().any(|elem| Self::returns_struct(elem, id)),
+            // a reference does not give access to a value of its own
+            _ => false,
+        }
+    }
+
+    /// Generates notes about structs in target modules which are not returned by any public
+    /// function, see `struct_has_public_constructor`.
+    pub fn warn_opaque_structs(&self) {
+        for (name, entry) in &self.struct_table {
+            if self.env.get_module(entry.module_id).is_target()
+                && !self.struct_has_public_constructor(entry.module_id.qualified(entry.struct_id))
+            {
+                self.env.diag(
+                    Severity::Note,
+                    &entry.loc,
+                    &format!(
+                        "struct `{}` is not returned by any public function",
+                        name.display(self.env)
+                    ),
+                );
+            }
+        }
+    }
+
     /// returns the type parameter kinds and the abilities of the struct
     fn get_struct_sig(&self, mid: ModuleId, sid: StructId) -> (Vec<TypeParameterKind>, AbilitySet) {
         let struct_entry = self.lookup_struct_entry(mid.qualified(sid));
@@ -435,39 +1591,669 @@ impl<'env> ModelBuilder<'env> {
     /// Specialized `ty::infer_and_check_abilities`
     /// where the abilities of type arguments are given by `ty_params`
     pub fn check_instantiation(&self, ty: &Type, ty_params: &[TypeParameter], loc: &Loc) {
-        infer_and_check_abilities(
-            ty,
+        match ty {
+            // function types are not descended into by `infer_and_check_abilities`,
+            // so check the instantiations of their argument and result types here
+            Type::Fun(args, result) => {
+                self.check_instantiation(args, ty_params, loc);
+                self.check_instantiation(result, ty_params, loc);
+            },
+            _ => {
+                infer_and_check_abilities(
+                    ty,
+                    gen_get_ty_param_kinds(ty_params),
+                    self.gen_get_struct_sig(),
+                    loc,
+                    |loc, ty_arg, err| match err {
+                        TypeArgError::MissingAbilities(missing) => self.error_with_code(
+                            loc,
+                            error_codes::MISSING_ABILITY,
+                            &self.explain_missing_abilities_of_arg(ty_arg, ty_params, *missing),
+                        ),
+                        TypeArgError::NotPhantomPosition => self.error(loc, &err.to_string()),
+                    },
+                );
+            },
+        }
+    }
+
+    /// Describes that the type argument `ty_arg` lacks the `missing` abilities. If the type
+    /// argument is a vector which lacks the missing abilities only because of its element type,
+    /// the element type is named, as in "`vector<T>` requires `T: drop`, but `T` is not
+    /// droppable". A `signer` type argument is explained by its fixed abilities.
+    fn explain_missing_abilities_of_arg(
+        &self,
+        ty_arg: &Type,
+        ty_params: &[TypeParameter],
+        missing: AbilitySet,
+    ) -> String {
+        let generic_msg = || format!("missing ability `{}`", missing);
+        let missing_abilities = missing.into_iter().collect_vec();
+        let describe = |abilities: &[Ability]| {
+            abilities
+                .iter()
+                .map(|a| match a {
+                    Ability::Copy => "copyable",
+                    Ability::Drop => "droppable",
+                    Ability::Store => "storable",
+                    Ability::Key => "a key",
+                })
+                .join(" and ")
+        };
+        if ty_arg.is_signer() {
+            return format!(
+                "`signer` is not {}, as it only has the `drop` ability",
+                describe(&missing_abilities)
+            );
+        }
+        let Type::Vector(mut elem) = ty_arg else {
+            return generic_msg();
+        };
+        while let Type::Vector(inner) = elem.as_ref() {
+            elem = inner
+        }
+        let elem_abilities = infer_abilities(
+            elem,
             gen_get_ty_param_kinds(ty_params),
             self.gen_get_struct_sig(),
-            loc,
-            |loc, _, err| self.error(loc, err),
         );
+        if missing_abilities
+            .iter()
+            .any(|a| !AbilitySet::VECTOR.has_ability(*a) || elem_abilities.has_ability(*a))
+        {
+            // Some missing ability is not caused by the element type
+            return generic_msg();
+        }
+        let tctx = TypeDisplayContext {
+            type_param_names: Some(
+                ty_params
+                    .iter()
+                    .map(|TypeParameter(name, ..)| *name)
+                    .collect(),
+            ),
+            ..self.type_display_context()
+        };
+        let elem_str = elem.display(&tctx).to_string();
+        format!(
+            "`{}` requires `{}: {}`, but `{}` is not {}",
+            ty_arg.display(&tctx),
+            elem_str,
+            missing_abilities.iter().map(|a| a.to_string()).join(" + "),
+            elem_str,
+            describe(&missing_abilities)
+        )
+    }
+
+    /// Generates a note if a generic struct is nested in its own type arguments deeper than
+    /// `max_self_instantiation_depth` within the field type `ty`, as such instantiations can
+    /// blow up monomorphization.
+    fn check_self_instantiation_depth(&self, ty: &Type, loc: &Loc) {
+        let Some(max_depth) = self.max_self_instantiation_depth else {
+            return;
+        };
+        fn nesting_depth(ty: &Type, mid: ModuleId, sid: StructId) -> usize {
+            match ty {
+                Type::Struct(m, s, args) if *m == mid && *s == sid => {
+                    1 + args
+                        .iter()
+                        .map(|arg| nesting_depth(arg, mid, sid))
+                        .max()
+                        .unwrap_or(0)
+                },
+                _ => 0,
+            }
+        }
+        let mut deepest: Option<(usize, ModuleId, StructId)> = None;
+        ty.visit(&mut |t| {
+            // Nesting a phantom-only struct does not affect the layout of the type.
+            if let Type::Struct(mid, sid, _) = t {
+                if self.is_phantom_only_struct(mid.qualified(*sid)) {
+                    return;
+                }
+                let depth = nesting_depth(t, *mid, *sid);
+                if deepest.map_or(true, |(d, _, _)| depth > d) {
+                    deepest = Some((depth, *mid, *sid));
+                }
+            }
+        });
+        if let Some((depth, mid, sid)) = deepest {
+            if depth > max_depth {
+                self.env.diag(
+                    Severity::Note,
+                    loc,
+                    &format!(
+                        "struct `{}` is nested {} levels deep in its own type arguments, \
+                         exceeding the limit of {}",
+                        self.reverse_struct_table[&(mid, sid)].display(self.env),
+                        depth,
+                        max_depth
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Determines whether the given struct is mapped to an intrinsic type, such that its
+    /// fields are opaque in specifications. This is known once the spec block of the struct
+    /// declaring the intrinsic has been analyzed.
+    pub fn is_abstract_spec_type(&self, id: QualifiedId<StructId>) -> bool {
+        self.abstract_spec_types.contains(&id)
+    }
+
+    /// Reports errors for selections of fields of abstract spec types in specifications, as
+    /// such structs are opaque there, see `is_abstract_spec_type`. Their fields can still be
+    /// used in code.
+    pub fn check_abstract_spec_type_usage(&self) {
+        if self.abstract_spec_types.is_empty() {
+            return;
+        }
+        let check_exp = |exp: &Exp| {
+            exp.visit_pre_order(&mut |e| {
+                if let ExpData::Call(id, Operation::Select(mid, sid, fid), _) = e {
+                    let struct_id = mid.qualified(*sid);
+                    if self.is_abstract_spec_type(struct_id) {
+                        self.error(
+                            &self.env.get_node_loc(*id),
+                            &format!(
+                                "field `{}` of `{}` cannot be selected in specifications, as \
+                                 the struct is mapped to an intrinsic type",
+                                fid.symbol().display(self.env.symbol_pool()),
+                                self.env.get_struct(struct_id).get_full_name_str()
+                            ),
+                        );
+                    }
+                }
+                true
+            });
+        };
+        let check_spec = |spec: &Spec| {
+            for cond in spec
+                .conditions
+                .iter()
+                .chain(spec.on_impl.values().flat_map(|s| s.conditions.iter()))
+            {
+                for exp in std::iter::once(&cond.exp).chain(&cond.additional_exps) {
+                    check_exp(exp)
+                }
+            }
+        };
+        for module in self.env.get_modules() {
+            check_spec(&module.get_spec());
+            for (_, decl) in module.get_spec_funs() {
+                if let Some(body) = &decl.body {
+                    check_exp(body)
+                }
+            }
+            for struct_env in module.get_structs() {
+                check_spec(&struct_env.get_spec());
+            }
+            for fun in module.get_functions() {
+                check_spec(&fun.get_spec());
+                if let Some(def) = fun.get_def() {
+                    def.visit_inline_specs(&mut |spec| {
+                        check_spec(spec);
+                        true
+                    });
+                }
+            }
+        }
+    }
+
+    /// Determines whether the given struct is generic and all its type parameters are phantom,
+    /// as is common for type-state patterns. The type arguments of such a struct never affect
+    /// its layout.
+    pub fn is_phantom_only_struct(&self, id: QualifiedId<StructId>) -> bool {
+        self.reverse_struct_table
+            .get(&(id.module_id, id.id))
+            .and_then(|name| self.struct_table.get(name))
+            .map_or(false, |entry| {
+                !entry.type_params.is_empty()
+                    && entry
+                        .type_params
+                        .iter()
+                        .all(|TypeParameter(_, kind, _)| kind.is_phantom)
+            })
     }
 
     /// Infers the abilities the given type may have,
     /// if all type params have all abilities.
     pub fn infer_abilities_may_have(&self, ty: &Type) -> AbilitySet {
-        // since all type params have all abilities, it doesn't matter whether it's phantom or not
-        infer_abilities(
-            ty,
-            |_| TypeParameterKind {
-                abilities: AbilitySet::ALL,
-                is_phantom: false,
+        match ty {
+            // a tuple has an ability only if all its elements have it
+            Type::Tuple(elems) => elems
+                .iter()
+                .map(|elem| self.infer_abilities_may_have(elem))
+                .fold(AbilitySet::PRIMITIVES, AbilitySet::intersect),
+            // function values can always be copied and dropped
+            Type::Fun(..) => AbilitySet::EMPTY.add(Ability::Copy).add(Ability::Drop),
+            // a signer can only be dropped, independent of any type parameters
+            Type::Primitive(PrimitiveType::Signer) => AbilitySet::SIGNER,
+            // since all type params have all abilities, it doesn't matter whether it's phantom or not
+            _ => infer_abilities(
+                ty,
+                |_| TypeParameterKind {
+                    abilities: AbilitySet::ALL,
+                    is_phantom: false,
+                },
+                self.gen_get_struct_sig(),
+            ),
+        }
+    }
+
+    /// Finds the innermost type within `ty` which lacks `ability` and thereby causes `ty` to
+    /// lack it, descending into vector elements, tuple elements, and the non-phantom type
+    /// arguments of structs which are declared with the ability. For a nested type like
+    /// `Table<u64, vector<V>>` lacking `store`, this is `V` if `V` is what lacks `store`.
+    /// Abilities are inferred as by `infer_abilities_may_have`. Returns `None` if `ty` has
+    /// the ability.
+    pub fn find_type_lacking_ability<'a>(
+        &self,
+        ty: &'a Type,
+        ability: Ability,
+    ) -> Option<&'a Type> {
+        if self.infer_abilities_may_have(ty).has_ability(ability) {
+            return None;
+        }
+        // a type argument needs `store` for the instantiation to have `key`
+        let arg_ability = if ability == Ability::Key {
+            Ability::Store
+        } else {
+            ability
+        };
+        let nested = match ty {
+            Type::Vector(elem) if AbilitySet::VECTOR.has_ability(ability) => {
+                self.find_type_lacking_ability(elem, arg_ability)
             },
-            self.gen_get_struct_sig(),
-        )
+            Type::Tuple(elems) => elems
+                .iter()
+                .find_map(|elem| self.find_type_lacking_ability(elem, ability)),
+            Type::Struct(mid, sid, args) => {
+                let (kinds, abilities) = self.get_struct_sig(*mid, *sid);
+                if abilities.has_ability(ability) {
+                    args.iter()
+                        .zip(kinds)
+                        .filter(|(_, kind)| !kind.is_phantom)
+                        .find_map(|(arg, _)| self.find_type_lacking_ability(arg, arg_ability))
+                } else {
+                    None
+                }
+            },
+            _ => None,
+        };
+        Some(nested.unwrap_or(ty))
+    }
+
+    /// Computes the abilities the given struct is required to have, based on its usage in the
+    /// fields of structs, in instantiations of generic structs, and in global storage operations
+    /// of the function bodies built so far. If the declared abilities of the struct do not
+    /// cover the result, the struct is used in a way its abilities do not allow.
+    pub fn required_abilities_for_struct(&self, id: QualifiedId<StructId>) -> AbilitySet {
+        let mut required = AbilitySet::EMPTY;
+        for entry in self.struct_table.values() {
+            for (_, _, ty) in entry.fields.iter().flat_map(|fields| fields.values()) {
+                required = required.union(self.required_abilities_in_type(
+                    id,
+                    ty,
+                    entry.abilities.requires(),
+                ));
+            }
+        }
+        for entry in self.fun_table.values() {
+            for Parameter(_, ty, _) in &entry.params {
+                required =
+                    required.union(self.required_abilities_in_type(id, ty, AbilitySet::EMPTY));
+            }
+            required = required.union(self.required_abilities_in_type(
+                id,
+                &entry.result_type,
+                AbilitySet::EMPTY,
+            ));
+            let fun = self
+                .env
+                .get_function(entry.module_id.qualified(entry.fun_id));
+            if let Some(body) = fun.get_def() {
+                body.visit_pre_order(&mut |e| {
+                    if let ExpData::Call(
+                        node_id,
+                        Operation::MoveTo
+                        | Operation::MoveFrom
+                        | Operation::BorrowGlobal(_)
+                        | Operation::Exists(_),
+                        _,
+                    ) = e
+                    {
+                        if let Some(ty) = self.env.get_node_instantiation(*node_id).first() {
+                            required = required.union(self.required_abilities_in_type(
+                                id,
+                                ty,
+                                AbilitySet::EMPTY.add(Ability::Key),
+                            ));
+                        }
+                    }
+                    true
+                });
+            }
+        }
+        required
+    }
+
+    /// Returns the locations which require the given struct to have `ability`, as considered
+    /// by `required_abilities_for_struct`: the fields of structs and the parameters of
+    /// functions whose types contain the struct, possibly nested in type arguments, the
+    /// functions whose result types do so, and the global storage operations of the function
+    /// bodies built so far. This explains, for instance, why a struct needs `store`.
+    pub fn ability_requirement_sources(
+        &self,
+        id: QualifiedId<StructId>,
+        ability: Ability,
+    ) -> Vec<Loc> {
+        let mut sources = vec![];
+        for entry in self.struct_table.values() {
+            for (loc, _, ty) in entry.fields.iter().flat_map(|fields| fields.values()) {
+                if self
+                    .required_abilities_in_type(id, ty, entry.abilities.requires())
+                    .has_ability(ability)
+                {
+                    sources.push(loc.clone());
+                }
+            }
+        }
+        for entry in self.fun_table.values() {
+            for Parameter(_, ty, loc) in &entry.params {
+                if self
+                    .required_abilities_in_type(id, ty, AbilitySet::EMPTY)
+                    .has_ability(ability)
+                {
+                    sources.push(loc.clone());
+                }
+            }
+            if self
+                .required_abilities_in_type(id, &entry.result_type, AbilitySet::EMPTY)
+                .has_ability(ability)
+            {
+                sources.push(entry.loc.clone());
+            }
+            let fun = self
+                .env
+                .get_function(entry.module_id.qualified(entry.fun_id));
+            if let Some(body) = fun.get_def() {
+                body.visit_pre_order(&mut |e| {
+                    if let ExpData::Call(
+                        node_id,
+                        Operation::MoveTo
+                        | Operation::MoveFrom
+                        | Operation::BorrowGlobal(_)
+                        | Operation::Exists(_),
+                        _,
+                    ) = e
+                    {
+                        if let Some(ty) = self.env.get_node_instantiation(*node_id).first() {
+                            if self
+                                .required_abilities_in_type(
+                                    id,
+                                    ty,
+                                    AbilitySet::EMPTY.add(Ability::Key),
+                                )
+                                .has_ability(ability)
+                            {
+                                sources.push(self.env.get_node_loc(*node_id));
+                            }
+                        }
+                    }
+                    true
+                });
+            }
+        }
+        sources
+    }
+
+    /// Computes the abilities the given struct is required to have by occurring in `ty`, where
+    /// `ty` itself is required to have the abilities `ty_required`. This accounts for the
+    /// constraints of type parameters of generic structs, as well as for phantom parameters.
+    fn required_abilities_in_type(
+        &self,
+        id: QualifiedId<StructId>,
+        ty: &Type,
+        ty_required: AbilitySet,
+    ) -> AbilitySet {
+        match ty {
+            Type::Struct(mid, sid, args) => {
+                let mut required = if mid.qualified(*sid) == id {
+                    ty_required
+                } else {
+                    AbilitySet::EMPTY
+                };
+                if let Some(name) = self.reverse_struct_table.get(&(*mid, *sid)) {
+                    let entry = &self.struct_table[name];
+                    for (arg, TypeParameter(_, kind, _)) in args.iter().zip(&entry.type_params) {
+                        let arg_required = if kind.is_phantom {
+                            kind.abilities
+                        } else {
+                            kind.abilities.union(ty_required.requires())
+                        };
+                        required =
+                            required.union(self.required_abilities_in_type(id, arg, arg_required));
+                    }
+                }
+                required
+            },
+            Type::Vector(elem) => self.required_abilities_in_type(id, elem, ty_required.requires()),
+            Type::Tuple(elems) => elems
+                .iter()
+                .map(|elem| self.required_abilities_in_type(id, elem, ty_required))
+                .fold(AbilitySet::EMPTY, AbilitySet::union),
+            Type::Reference(_, inner) => {
+                self.required_abilities_in_type(id, inner, AbilitySet::EMPTY)
+            },
+            Type::Fun(args, result) => self
+                .required_abilities_in_type(id, args, AbilitySet::EMPTY)
+                .union(self.required_abilities_in_type(id, result, AbilitySet::EMPTY)),
+            _ => AbilitySet::EMPTY,
+        }
     }
 
     /// Checks whether a struct is well defined.
     pub fn ability_check_struct_def(&self, struct_entry: &StructEntry) {
         if let Some(fields) = &struct_entry.fields {
             let ty_params = &struct_entry.type_params;
-            for (_field_name, (loc, _field_idx, field_ty)) in fields.iter() {
+            let is_target = self.in_target_package(struct_entry.module_id, &struct_entry.loc);
+            for (field_name, (loc, _field_idx, field_ty)) in fields.iter() {
                 // check fields are properly instantiated
                 self.check_instantiation(field_ty, ty_params, loc);
+                self.check_self_instantiation_depth(field_ty, loc);
                 if is_phantom_type_arg(gen_get_ty_param_kinds(ty_params), field_ty) {
                     self.error(loc, "phantom type arguments cannot be used")
                 }
+                // check fields are representable in code; erroneous types have been reported
+                // already
+                let mut has_error = false;
+                field_ty.visit(&mut |t| has_error |= matches!(t, Type::Error));
+                if field_ty.is_spec() && !has_error {
+                    let tctx = TypeDisplayContext {
+                        type_param_names: Some(
+                            ty_params
+                                .iter()
+                                .map(|TypeParameter(name, ..)| *name)
+                                .collect(),
+                        ),
+                        ..self.type_display_context()
+                    };
+                    self.error(
+                        loc,
+                        &format!(
+                            "field type `{}` is not allowed in a struct",
+                            field_ty.display(&tctx)
+                        ),
+                    )
+                }
+                // suggest reviewing integer fields wider than needed for most values
+                if self.warn_wide_integer_fields
+                    && is_target
+                    && matches!(
+                        field_ty,
+                        Type::Primitive(PrimitiveType::U128 | PrimitiveType::U256)
+                    )
+                {
+                    self.env.diag(
+                        Severity::Note,
+                        loc,
+                        &format!(
+                            "field `{}` has type `{}`; consider whether a type no wider \
+                             than `u64` suffices",
+                            field_name.display(self.env.symbol_pool()),
+                            field_ty.display(&self.type_display_context())
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Checks whether the named struct is well defined, unless `defer_ability_checks` is set,
+    /// in which case the check is postponed until `run_deferred_ability_checks` is called.
+    pub fn ability_check_or_defer_struct_def(&mut self, name: &QualifiedSymbol) {
+        if self.defer_ability_checks {
+            self.deferred_ability_checks.push(name.clone());
+        } else {
+            self.ability_check_struct_def(self.struct_table.get(name).expect("struct invalid"));
+        }
+    }
+
+    /// Runs the struct definition ability checks deferred so far. This must be called after
+    /// all modules have been added, so that all structs used in field types are known.
+    pub fn run_deferred_ability_checks(&mut self) {
+        let deferred = std::mem::take(&mut self.deferred_ability_checks);
+        for name in &deferred {
+            self.ability_check_struct_def(self.struct_table.get(name).expect("struct invalid"));
+        }
+        // Only now all usages of the structs are known.
+        for name in &deferred {
+            self.explain_missing_abilities(name);
+        }
+        self.check_recursive_phantom_params();
+        self.ability_checks_done = true;
+    }
+
+    /// Generates a note if the named struct lacks abilities which it is required to have by its
+    /// usage, as computed by `required_abilities_for_struct`. The usages themselves are reported
+    /// as errors where they occur; the note names the struct which needs to be changed, and
+    /// labels the locations requiring each missing ability.
+    fn explain_missing_abilities(&self, name: &QualifiedSymbol) {
+        let entry = self.struct_table.get(name).expect("struct invalid");
+        let id = entry.module_id.qualified(entry.struct_id);
+        let missing = self
+            .required_abilities_for_struct(id)
+            .setminus(entry.abilities);
+        if missing == AbilitySet::EMPTY {
+            return;
+        }
+        let labels = Ability::all()
+            .filter(|ability| missing.has_ability(*ability))
+            .flat_map(|ability| {
+                self.ability_requirement_sources(id, ability)
+                    .into_iter()
+                    .map(move |loc| (loc, format!("requires `{}`", ability)))
+            })
+            .collect_vec();
+        self.env.diag_with_labels(
+            Severity::Note,
+            &entry.loc,
+            &format!(
+                "struct `{}` lacks {} required by its usage",
+                name.display(self.env),
+                self.ability_names(missing)
+                    .iter()
+                    .map(|name| format!("`{}`", name))
+                    .join(", ")
+            ),
+            labels,
+        );
+    }
+
+    /// Checks that within a group of mutually recursive structs, a phantom type parameter is
+    /// not threaded into a non-phantom type parameter of a peer struct, not even nested as in
+    /// `B<vector<T>>`. Passing the phantom parameter directly is already reported by
+    /// `ability_check_struct_def`. The groups are the strongly connected components of the
+    /// graph of struct field types, computed once. Nested occurrences in structs outside of a
+    /// recursive group, as in `Other<vector<T>>`, are not reported.
+    fn check_recursive_phantom_params(&self) {
+        let mut graph = DiGraphMap::<QualifiedId<StructId>, ()>::new();
+        for entry in self.struct_table.values() {
+            let id = entry.module_id.qualified(entry.struct_id);
+            graph.add_node(id);
+            for (_, (_, _, ty)) in entry.fields.iter().flatten() {
+                ty.visit(&mut |t| {
+                    if let Type::Struct(mid, sid, _) = t {
+                        graph.add_edge(id, mid.qualified(*sid), ());
+                    }
+                });
+            }
+        }
+        let mut group_of = BTreeMap::new();
+        for (group, scc) in tarjan_scc(&graph).into_iter().enumerate() {
+            if scc.len() > 1 || graph.contains_edge(scc[0], scc[0]) {
+                group_of.extend(scc.into_iter().map(|id| (id, group)));
+            }
+        }
+        let is_peer = |id1: QualifiedId<StructId>, id2: QualifiedId<StructId>| {
+            group_of.contains_key(&id1) && group_of.get(&id1) == group_of.get(&id2)
+        };
+        for entry in self.struct_table.values() {
+            let id = entry.module_id.qualified(entry.struct_id);
+            let ty_params = &entry.type_params;
+            if !ty_params
+                .iter()
+                .any(|TypeParameter(_, kind, _)| kind.is_phantom)
+            {
+                continue;
+            }
+            for (_, (loc, _, field_ty)) in entry.fields.iter().flatten() {
+                field_ty.visit(&mut |t| {
+                    let Type::Struct(mid, sid, args) = t else {
+                        return;
+                    };
+                    let peer = mid.qualified(*sid);
+                    if !is_peer(id, peer) {
+                        return;
+                    }
+                    let peer_params = &self.lookup_struct_entry(peer).type_params;
+                    for (arg, TypeParameter(peer_param, peer_kind, _)) in
+                        args.iter().zip(peer_params)
+                    {
+                        if peer_kind.is_phantom
+                            || is_phantom_type_arg(gen_get_ty_param_kinds(ty_params), arg)
+                        {
+                            continue;
+                        }
+                        let mut phantom_params = BTreeSet::new();
+                        arg.visit(&mut |t| {
+                            if let Type::TypeParameter(idx) = t {
+                                if let Some(TypeParameter(name, kind, _)) =
+                                    ty_params.get(*idx as usize)
+                                {
+                                    if kind.is_phantom {
+                                        phantom_params.insert(*name);
+                                    }
+                                }
+                            }
+                        });
+                        for name in phantom_params {
+                            self.error(
+                                loc,
+                                &format!(
+                                    "phantom type parameter `{}` of `{}` is passed to the \
+                                     non-phantom type parameter `{}` of the recursive struct `{}`",
+                                    name.display(self.env.symbol_pool()),
+                                    self.reverse_struct_table[&(id.module_id, id.id)]
+                                        .display(self.env),
+                                    peer_param.display(self.env.symbol_pool()),
+                                    self.reverse_struct_table[&(peer.module_id, peer.id)]
+                                        .display(self.env)
+                                ),
+                            );
+                        }
+                    }
+                });
             }
         }
     }
@@ -490,6 +2276,726 @@ impl<'env> ModelBuilder<'env> {
         }
     }
 
+    /// Generates notes about spec functions in target modules which are not used by any
+    /// specification. Spec functions whose name starts with `_` are not reported.
+    pub fn warn_unused_spec_funs(&self) {
+        for (name, entries) in &self.spec_fun_table {
+            let fun_name = name.display_simple(self.env).to_string();
+            for entry in entries {
+                if let Operation::SpecFunction(module_id, fun_id, None) = &entry.oper {
+                    if self.env.get_module(*module_id).is_target()
+                        && !fun_name.starts_with('_')
+                        && !self.env.is_spec_fun_used(module_id.qualified(*fun_id))
+                    {
+                        self.env.diag(
+                            Severity::Note,
+                            &entry.loc,
+                            &format!("unused spec function {}", name.display(self.env)),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Generates notes about schemas in target modules which are included by the specs of
+    /// functions, listing these functions.
+    pub fn report_schema_applications(&self) {
+        for (name, entry) in &self.spec_schema_table {
+            if !self.env.get_module(entry.module_id).is_target() {
+                continue;
+            }
+            let funs = self.schema_applications(name);
+            if funs.is_empty() {
+                continue;
+            }
+            self.env.diag(
+                Severity::Note,
+                &entry.loc,
+                &format!(
+                    "schema `{}` is included by the specs of {}",
+                    name.display(self.env),
+                    funs.iter()
+                        .map(|id| format!("`{}`", self.env.get_function(*id).get_full_name_str()))
+                        .join(", ")
+                ),
+            );
+        }
+    }
+
+    /// Generates notes about public functions in target modules which have neither a spec
+    /// block nor inline specs. Functions marked with `#[test_only]`, or declared in a module
+    /// marked with it, are exempt.
+    pub fn warn_unspecified_public_funs(&self) {
+        let is_test_only = |attributes: &[Attribute]| {
+            attributes
+                .iter()
+                .any(|attr| self.env.symbol_pool().string(attr.name()).as_str() == "test_only")
+        };
+        for (name, entry) in &self.fun_table {
+            let module = self.env.get_module(entry.module_id);
+            if !module.is_target()
+                || entry.visibility != Visibility::Public
+                || !entry.inline_specs.is_empty()
+                || is_test_only(&entry.attributes)
+                || is_test_only(module.get_attributes())
+            {
+                continue;
+            }
+            let fun = self
+                .env
+                .get_function(entry.module_id.qualified(entry.fun_id));
+            if fun.get_spec().is_empty() {
+                self.env.diag(
+                    Severity::Note,
+                    &entry.name_loc,
+                    &format!(
+                        "public function `{}` has no specification",
+                        name.display(self.env)
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Checks that the conditions of each schema, including those of its included schemas, only
+    /// reference variables which are in scope of the schema, i.e. declared by it or one of its
+    /// included schemas, or bound by one of their lets. References to other variables indicate
+    /// a faulty translation of the schema, for instance of an include.
+    pub fn check_schema_vars(&self) {
+        for entry in self.spec_schema_table.values() {
+            let conditions = || {
+                entry
+                    .spec
+                    .conditions
+                    .iter()
+                    .chain(entry.included_spec.conditions.iter())
+            };
+            let lets = conditions()
+                .filter_map(|cond| match &cond.kind {
+                    ConditionKind::LetPre(sym, _) | ConditionKind::LetPost(sym, _) => Some(*sym),
+                    _ => None,
+                })
+                .collect::<BTreeSet<_>>();
+            for exp in conditions()
+                .flat_map(|cond| std::iter::once(&cond.exp).chain(cond.additional_exps.iter()))
+            {
+                let undeclared = exp
+                    .free_vars()
+                    .into_iter()
+                    .filter(|sym| !entry.all_vars.contains_key(sym) && !lets.contains(sym))
+                    .collect::<BTreeSet<_>>();
+                if undeclared.is_empty() {
+                    continue;
+                }
+                exp.visit_pre_order(&mut |e| {
+                    if let ExpData::LocalVar(id, sym) = e {
+                        if undeclared.contains(sym) {
+                            self.error(
+                                &self.env.get_node_loc(*id),
+                                &format!(
+                                    "undeclared variable `{}` in schema `{}`",
+                                    sym.display(self.env.symbol_pool()),
+                                    entry.name.display(self.env)
+                                ),
+                            );
+                        }
+                    }
+                    true
+                });
+            }
+        }
+    }
+
+    /// Generates notes about numeric abort codes in the `aborts_with` conditions of functions
+    /// in target modules which neither refer to a constant nor equal the value of a `u64`
+    /// constant declared in the function's module. Such codes are often stale.
+    pub fn warn_unknown_abort_codes(&self) {
+        for entry in self.fun_table.values() {
+            if !self.env.get_module(entry.module_id).is_target() {
+                continue;
+            }
+            let module_name = &self.env.module_data[entry.module_id.to_usize()].name;
+            let const_values = self
+                .consts_of_type(&Type::Primitive(PrimitiveType::U64))
+                .into_iter()
+                .filter(|(name, _)| &name.module_name == module_name)
+                .map(|(_, value)| value)
+                .collect_vec();
+            let fun = self
+                .env
+                .get_function(entry.module_id.qualified(entry.fun_id));
+            let spec = fun.get_spec();
+            for cond in spec.filter_kind(ConditionKind::AbortsWith) {
+                for code in std::iter::once(&cond.exp).chain(cond.additional_exps.iter()) {
+                    if let ExpData::Value(id, value @ Value::Number(n)) = code.as_ref() {
+                        if !self.const_references.contains_key(id) && !const_values.contains(&value)
+                        {
+                            self.env.diag(
+                                Severity::Note,
+                                &self.env.get_node_loc(*id),
+                                &format!(
+                                    "abort code `{}` does not correspond to any constant of \
+                                     module `{}`",
+                                    n,
+                                    module_name.display(self.env)
+                                ),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Generates notes about integer constants in target modules whose value is zero, the
+    /// default value of their type, as such constants may be redundant.
+    pub fn warn_zero_constants(&self) {
+        for (name, entry) in &self.const_table {
+            // Constants of the builtin pseudo module have no module in the environment.
+            let in_target = self
+                .env
+                .find_module(&name.module_name)
+                .map_or(false, |module| {
+                    self.in_target_package(module.get_id(), &entry.loc)
+                });
+            if !in_target || !entry.ty.is_number() {
+                continue;
+            }
+            if matches!(&entry.value, Value::Number(n) if n.is_zero()) {
+                self.env.diag(
+                    Severity::Note,
+                    &entry.loc,
+                    &format!(
+                        "constant `{}` is zero, the default value of its type; consider whether \
+                         it is needed",
+                        name.display(self.env)
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Generates notes about structs in target modules which have more than `threshold` fields.
+    pub fn warn_wide_structs(&self, threshold: usize) {
+        for (name, entry) in &self.struct_table {
+            let field_count = entry.fields.as_ref().map_or(0, |fields| fields.len());
+            if field_count > threshold && self.env.get_module(entry.module_id).is_target() {
+                self.env.diag(
+                    Severity::Note,
+                    &entry.loc,
+                    &format!(
+                        "struct `{}` has {} fields, which exceeds the threshold of {}",
+                        name.display(self.env),
+                        field_count,
+                        threshold
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Returns the deepest nesting of generic struct instantiations in the types declared by
+    /// the given module, i.e. the types of struct fields, function and spec function signatures,
+    /// and constants. A non-generic struct has depth 1, and `A<B<C>>` has depth 3. Vectors,
+    /// references, tuples and function types do not add to the depth.
+    pub fn max_instantiation_depth(&self, module_id: ModuleId) -> usize {
+        fn depth(ty: &Type) -> usize {
+            match ty {
+                Type::Struct(_, _, args) => 1 + args.iter().map(depth).max().unwrap_or(0),
+                Type::Vector(elem) | Type::Reference(_, elem) => depth(elem),
+                Type::Tuple(elems) => elems.iter().map(depth).max().unwrap_or(0),
+                Type::Fun(args, result) => depth(args).max(depth(result)),
+                _ => 0,
+            }
+        }
+        let mut types: Vec<&Type> = vec![];
+        for entry in self.struct_table.values() {
+            if entry.module_id == module_id {
+                types.extend(entry.fields.iter().flatten().map(|(_, (_, _, ty))| ty));
+            }
+        }
+        for entry in self.fun_table.values() {
+            if entry.module_id == module_id {
+                types.extend(entry.params.iter().map(|Parameter(_, ty, _)| ty));
+                types.push(&entry.result_type);
+            }
+        }
+        for entry in self.spec_fun_table.values().flatten() {
+            if matches!(entry.oper, Operation::SpecFunction(mid, ..) if mid == module_id) {
+                types.extend(entry.params.iter().map(|Parameter(_, ty, _)| ty));
+                types.push(&entry.result_type);
+            }
+        }
+        if let Some(data) = self.env.module_data.get(module_id.to_usize()) {
+            types.extend(
+                self.const_table
+                    .iter()
+                    .filter(|(name, _)| name.module_name == data.name)
+                    .map(|(_, entry)| &entry.ty),
+            );
+        }
+        types.into_iter().map(depth).max().unwrap_or(0)
+    }
+
+    /// Generates a note for each target module whose declared types contain generic struct
+    /// instantiations nested deeper than `limit`, see `max_instantiation_depth`.
+    pub fn warn_deep_instantiations(&self, limit: usize) {
+        for module in self.env.get_modules() {
+            if !self.in_target_package(module.get_id(), &module.get_loc()) {
+                continue;
+            }
+            let depth = self.max_instantiation_depth(module.get_id());
+            if depth > limit {
+                self.env.diag(
+                    Severity::Note,
+                    &module.get_loc(),
+                    &format!(
+                        "module `{}` has generic instantiations of depth {}, which exceeds the \
+                         limit of {}",
+                        module.get_name().display(self.env),
+                        depth,
+                        limit
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Generates notes about structs in target modules which have the same field layout, i.e.
+    /// the same field types in declaration order, but differ in their declared abilities.
+    /// Structs without fields are not considered.
+    pub fn warn_layout_duplicate_structs(&self) {
+        let dummy_field = self.env.symbol_pool().make("dummy_field");
+        let mut layouts: BTreeMap<Vec<Type>, Vec<(&QualifiedSymbol, &StructEntry)>> =
+            BTreeMap::new();
+        for (name, entry) in &self.struct_table {
+            if let Some(fields) = &entry.fields {
+                if (fields.len() == 1 && fields.contains_key(&dummy_field))
+                    || !self.env.get_module(entry.module_id).is_target()
+                {
+                    continue;
+                }
+                let layout = fields
+                    .values()
+                    .sorted_by_key(|(_, offset, _)| *offset)
+                    .map(|(_, _, ty)| ty.clone())
+                    .collect_vec();
+                layouts.entry(layout).or_default().push((name, entry));
+            }
+        }
+        for structs in layouts.values() {
+            let (_, first) = structs[0];
+            if structs.iter().all(|(_, e)| e.abilities == first.abilities) {
+                continue;
+            }
+            let mut labels = structs
+                .iter()
+                .map(|(name, entry)| {
+                    let abilities = if entry.abilities == AbilitySet::EMPTY {
+                        "no abilities".to_owned()
+                    } else {
+                        format!("abilities `{}`", entry.abilities)
+                    };
+                    (
+                        entry.loc.clone(),
+                        format!(
+                            "`{}` declared with {}",
+                            name.display_simple(self.env),
+                            abilities
+                        ),
+                    )
+                })
+                .collect_vec();
+            let (_, primary) = labels.remove(0);
+            self.env.diag_with_primary_and_labels(
+                Severity::Note,
+                &first.loc,
+                &format!(
+                    "structs {} have the same field layout but different abilities",
+                    structs
+                        .iter()
+                        .map(|(name, _)| format!("`{}`", name.display_simple(self.env)))
+                        .join(", ")
+                ),
+                &primary,
+                labels,
+            );
+        }
+    }
+
+    /// Generates notes about public functions in target packages whose signature exposes a
+    /// struct which is internal to another package. A struct is considered usable in such a
+    /// signature if it is declared in the same module, has the `store` ability, is returned by
+    /// a public function of its own module, is declared in a module which has the function's
+    /// module as a friend, or belongs to the same package. Structs handed out by their module,
+    /// as capabilities, are thereby meant for external use. If the package of a module is not
+    /// known, modules are considered to be in the same package if both are in target packages
+    /// or both are not, see `in_target_package`.
+    pub fn check_public_api_types(&self) {
+        for (name, entry) in &self.fun_table {
+            let fun_in_target = self.in_target_package(entry.module_id, &entry.loc);
+            if entry.visibility != Visibility::Public || !fun_in_target {
+                continue;
+            }
+            let mut exposed = BTreeSet::new();
+            let mut collect = |ty: &Type| {
+                if let Type::Struct(mid, sid, _) = ty {
+                    exposed.insert(mid.qualified(*sid));
+                }
+            };
+            for Parameter(_, ty, _) in &entry.params {
+                ty.visit(&mut collect);
+            }
+            entry.result_type.visit(&mut collect);
+            for struct_id in exposed {
+                if struct_id.module_id == entry.module_id {
+                    continue;
+                }
+                let Some(struct_name) = self
+                    .reverse_struct_table
+                    .get(&(struct_id.module_id, struct_id.id))
+                else {
+                    continue;
+                };
+                let struct_entry = &self.struct_table[struct_name];
+                if struct_entry.abilities.has_ability(Ability::Store)
+                    || self.fun_table.values().any(|fun_entry| {
+                        fun_entry.module_id == struct_id.module_id
+                            && fun_entry.visibility == Visibility::Public
+                            && Self::returns_struct(&fun_entry.result_type, struct_id)
+                    })
+                    || self.declares_friend(struct_id.module_id, &name.module_name)
+                    || match (
+                        self.package_of(struct_id.module_id),
+                        self.package_of(entry.module_id),
+                    ) {
+                        (Some(struct_package), Some(fun_package)) => struct_package == fun_package,
+                        _ => {
+                            self.in_target_package(struct_id.module_id, &struct_entry.loc)
+                                == fun_in_target
+                        },
+                    }
+                {
+                    continue;
+                }
+                self.env.diag_with_labels(
+                    Severity::Note,
+                    &entry.name_loc,
+                    &format!(
+                        "public function `{}` exposes struct `{}` which is internal to another package",
+                        name.display_simple(self.env),
+                        struct_name.display(self.env)
+                    ),
+                    vec![(
+                        struct_entry.loc.clone(),
+                        "struct declared here without the `store` ability".to_owned(),
+                    )],
+                );
+            }
+        }
+    }
+
+    /// Checks the attributes of structs and functions in target modules against the registered
+    /// attribute schemas, generating warnings about malformed arguments. Attributes without a
+    /// schema, as those a flavor adds to the compiler's known attributes, are not checked here;
+    /// unknown attribute names are already reported by the compiler.
+    pub fn check_attributes(&self) {
+        let is_target = |module_id: ModuleId| self.env.get_module(module_id).is_target();
+        for entry in self.struct_table.values() {
+            if is_target(entry.module_id) {
+                self.check_attribute_list(&entry.attributes);
+            }
+        }
+        for entry in self.fun_table.values() {
+            if is_target(entry.module_id) {
+                self.check_attribute_list(&entry.attributes);
+            }
+        }
+    }
+
+    fn check_attribute_list(&self, attrs: &[Attribute]) {
+        for attr in attrs {
+            let loc = self.env.get_node_loc(attr.node_id());
+            let name_str = self.env.symbol_pool().string(attr.name());
+            let Some(schema) = self.attribute_schemas.get(name_str.as_str()) else {
+                continue;
+            };
+            match attr {
+                Attribute::Apply(_, _, args) if !schema.any_args => {
+                    for arg in args {
+                        let arg_name = self.env.symbol_pool().string(arg.name());
+                        if !schema.args.contains(arg_name.as_str()) {
+                            self.env.diag(
+                                Severity::Warning,
+                                &self.env.get_node_loc(arg.node_id()),
+                                &format!(
+                                    "unexpected argument `{}` for attribute `{}`",
+                                    arg_name, name_str
+                                ),
+                            );
+                        }
+                    }
+                },
+                Attribute::Assign(..) if !schema.assignable => {
+                    self.env.diag(
+                        Severity::Warning,
+                        &loc,
+                        &format!("attribute `{}` cannot be assigned a value", name_str),
+                    );
+                },
+                _ => {},
+            }
+        }
+    }
+
+    /// Returns the name of the package the given module originates from, if known.
+    pub fn package_of(&self, module_id: ModuleId) -> Option<Symbol> {
+        self.module_packages.get(&module_id).copied()
+    }
+
+    /// Determines whether the given module, declared at `loc`, belongs to a target package. If
+    /// the package of the module is not known, this is the case unless the module comes from a
+    /// dependency file. Unlike `ModuleEnv::is_target`, this can be used while the module is
+    /// being translated.
+    pub fn in_target_package(&self, module_id: ModuleId, loc: &Loc) -> bool {
+        match self.package_of(module_id) {
+            Some(package) => self.target_packages.contains(&package),
+            None => !self.env.file_id_is_dep.contains(&loc.file_id()),
+        }
+    }
+
+    /// Determines whether the given module declares the named module as a friend. This is
+    /// based on the friend declarations, hence also works before friend module ids are known.
+    fn declares_friend(&self, module_id: ModuleId, friend: &ModuleName) -> bool {
+        self.env.module_data[module_id.to_usize()]
+            .friend_decls
+            .iter()
+            .any(|decl| &decl.module_name == friend)
+    }
+
+    /// Computes the accessibility of the given function when called from module `from`,
+    /// based on the visibility of the function and the friend declarations of its module.
+    #[allow(dead_code)]
+    pub fn effective_visibility(
+        &self,
+        id: QualifiedId<FunId>,
+        from: ModuleId,
+    ) -> EffectiveVisibility {
+        let name = QualifiedSymbol {
+            module_name: self.env.module_data[id.module_id.to_usize()].name.clone(),
+            symbol: id.id.symbol(),
+        };
+        let entry = self.fun_table.get(&name).expect("function defined");
+        if id.module_id == from {
+            return EffectiveVisibility::Accessible;
+        }
+        match entry.visibility {
+            Visibility::Public => EffectiveVisibility::Accessible,
+            Visibility::Friend => {
+                let from_name = &self.env.module_data[from.to_usize()].name;
+                if self.declares_friend(id.module_id, from_name) {
+                    EffectiveVisibility::Accessible
+                } else {
+                    EffectiveVisibility::FriendOnly
+                }
+            },
+            Visibility::Private => EffectiveVisibility::Inaccessible,
+        }
+    }
+
+    /// Generates notes about groups of functions in target modules which have identical bodies,
+    /// modulo the names of locals, suggesting to extract the common code. Function bodies are
+    /// only available if the model is built with `ModelBuilderOptions::compile_via_model`.
+    /// Bodies with less than `MIN_BODY_SIZE` expression nodes are not considered.
+    pub fn warn_cloned_functions(&self) {
+        const MIN_BODY_SIZE: usize = 10;
+        let mut groups: BTreeMap<Vec<String>, Vec<(&QualifiedSymbol, &FunEntry)>> = BTreeMap::new();
+        for (name, entry) in &self.fun_table {
+            if !self.env.get_module(entry.module_id).is_target() {
+                continue;
+            }
+            let fun = self
+                .env
+                .get_function(entry.module_id.qualified(entry.fun_id));
+            if let Some(body) = fun.get_def() {
+                let shape = normalized_exp_shape(body);
+                // Each expression node contributes an opening and a closing token.
+                if shape.len() / 2 >= MIN_BODY_SIZE {
+                    groups.entry(shape).or_default().push((name, entry));
+                }
+            }
+        }
+        for funs in groups.values().filter(|funs| funs.len() > 1) {
+            let body_label =
+                |name: &QualifiedSymbol| format!("body of `{}`", name.display(self.env));
+            let (first_name, first) = funs[0];
+            let labels = funs[1..]
+                .iter()
+                .map(|(name, entry)| (entry.loc.clone(), body_label(name)))
+                .collect_vec();
+            self.env.diag_with_primary_and_labels(
+                Severity::Note,
+                &first.loc,
+                &format!(
+                    "functions {} have identical bodies; consider extracting the common code \
+                     into a shared function",
+                    funs.iter()
+                        .map(|(name, _)| format!("`{}`", name.display(self.env)))
+                        .join(", ")
+                ),
+                &body_label(first_name),
+                labels,
+            );
+        }
+    }
+
+    /// Generates notes about parameters of functions in target modules which are not used in
+    /// the function body, including its inline specs. Parameters whose name starts with `_`
+    /// are exempt. Function bodies are only available if the model is built with
+    /// `ModelBuilderOptions::compile_via_model`.
+    pub fn warn_unused_params(&self) {
+        for entry in self.fun_table.values() {
+            if !self.env.get_module(entry.module_id).is_target() {
+                continue;
+            }
+            let fun = self
+                .env
+                .get_function(entry.module_id.qualified(entry.fun_id));
+            let Some(body) = fun.get_def() else {
+                continue;
+            };
+            let mut used_temps = BTreeSet::new();
+            let mut used_locals = BTreeSet::new();
+            body.visit_pre_order(&mut |e| {
+                match e {
+                    ExpData::Temporary(_, idx) => {
+                        used_temps.insert(*idx);
+                    },
+                    ExpData::LocalVar(_, sym) => {
+                        used_locals.insert(*sym);
+                    },
+                    _ => {},
+                }
+                true
+            });
+            for (idx, Parameter(name, _, loc)) in entry.params.iter().enumerate() {
+                let name_str = self.env.symbol_pool().string(*name);
+                if name_str.starts_with('_')
+                    || used_temps.contains(&idx)
+                    || used_locals.contains(name)
+                {
+                    continue;
+                }
+                self.env.diag(
+                    Severity::Note,
+                    loc,
+                    &format!(
+                        "unused parameter `{}`; consider removing it or prefixing it with `_`",
+                        name_str
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Checks that the type parameters which occur in the instantiations of global storage
+    /// operations in the bodies of functions in target modules are declared with the abilities
+    /// these operations require. The resource type must have `key`, hence a type parameter
+    /// instantiating the operation must have `key`, and one in a non-phantom position of the
+    /// resource struct must have `store`. Since type parameters are assumed to have all
+    /// abilities during type checking, this would otherwise only be detected later in the
+    /// pipeline. Function bodies are only available if the model is built with
+    /// `ModelBuilderOptions::compile_via_model`.
+    pub fn check_type_param_abilities(&self) {
+        for entry in self.fun_table.values() {
+            if !self.env.get_module(entry.module_id).is_target() {
+                continue;
+            }
+            let fun = self
+                .env
+                .get_function(entry.module_id.qualified(entry.fun_id));
+            let Some(body) = fun.get_def() else {
+                continue;
+            };
+            body.visit_pre_order(&mut |e| {
+                if let ExpData::Call(
+                    id,
+                    Operation::MoveTo
+                    | Operation::MoveFrom
+                    | Operation::BorrowGlobal(_)
+                    | Operation::Exists(_),
+                    _,
+                ) = e
+                {
+                    if let Some(ty) = self.env.get_node_instantiation(*id).first() {
+                        let mut missing = BTreeMap::new();
+                        self.missing_type_param_abilities(
+                            ty,
+                            AbilitySet::EMPTY.add(Ability::Key),
+                            &entry.type_params,
+                            &mut missing,
+                        );
+                        for (idx, abilities) in missing {
+                            let TypeParameter(name, ..) = &entry.type_params[idx as usize];
+                            self.error(
+                                &self.env.get_node_loc(*id),
+                                &format!(
+                                    "type parameter `{}` must have {} `{}`",
+                                    name.display(self.env.symbol_pool()),
+                                    if abilities.into_iter().count() == 1 {
+                                        "ability"
+                                    } else {
+                                        "abilities"
+                                    },
+                                    abilities
+                                ),
+                            );
+                        }
+                    }
+                }
+                true
+            });
+        }
+    }
+
+    /// Collects in `missing` the abilities which the type parameters occurring in `ty` lack,
+    /// according to their declaration in `type_params`, for `ty` to have the `required`
+    /// abilities. Abilities which `ty` lacks independent of type parameters are not collected.
+    fn missing_type_param_abilities(
+        &self,
+        ty: &Type,
+        required: AbilitySet,
+        type_params: &[TypeParameter],
+        missing: &mut BTreeMap<u16, AbilitySet>,
+    ) {
+        match ty {
+            Type::TypeParameter(idx) => {
+                if let Some(TypeParameter(_, kind, _)) = type_params.get(*idx as usize) {
+                    if !required.is_subset(kind.abilities) {
+                        let lacking = missing.entry(*idx).or_insert(AbilitySet::EMPTY);
+                        *lacking = lacking.union(required.setminus(kind.abilities));
+                    }
+                }
+            },
+            Type::Vector(elem) => {
+                self.missing_type_param_abilities(elem, required, type_params, missing)
+            },
+            Type::Struct(mid, sid, args) => {
+                let (params, _) = self.get_struct_sig(*mid, *sid);
+                for (param, arg) in params.iter().zip(args) {
+                    let arg_required = if param.is_phantom {
+                        param.abilities
+                    } else {
+                        param.abilities.union(required.requires())
+                    };
+                    self.missing_type_param_abilities(arg, arg_required, type_params, missing)
+                }
+            },
+            _ => {},
+        }
+    }
+
     /// Returns the symbol for a binary op.
     pub fn bin_op_symbol(&self, op: &PA::BinOp_) -> QualifiedSymbol {
         QualifiedSymbol {
@@ -498,6 +3004,39 @@ impl<'env> ModelBuilder<'env> {
         }
     }
 
+    /// Returns the module in which the binary op should be resolved for the given operand type.
+    /// If the operand is a struct for which an overload of the op has been registered via
+    /// `register_operator_overload`, this is the module of the struct, otherwise the builtin
+    /// module.
+    pub fn resolve_op_module(&self, op: &PA::BinOp_, operand: &Type) -> ModuleName {
+        if let Type::Struct(mid, sid, _) = operand.skip_reference() {
+            let op_symbol = self.env.symbol_pool().make(op.symbol());
+            if self
+                .operator_overloads
+                .contains_key(&(mid.qualified(*sid), op_symbol))
+            {
+                return self.reverse_struct_table[&(*mid, *sid)].module_name.clone();
+            }
+        }
+        self.builtin_module()
+    }
+
+    /// Returns the overloadable binary op whose symbol is `name` in the builtin module, if any.
+    pub fn overloadable_bin_op(
+        &self,
+        module_name: &ModuleName,
+        name: Symbol,
+    ) -> Option<PA::BinOp_> {
+        if module_name != &self.builtin_module() {
+            return None;
+        }
+        let name = self.env.symbol_pool().string(name);
+        OVERLOADABLE_OPS
+            .iter()
+            .find(|op| op.symbol() == name.as_str())
+            .copied()
+    }
+
     /// Returns the symbol for a unary op.
     pub fn unary_op_symbol(&self, op: &PA::UnaryOp_) -> QualifiedSymbol {
         QualifiedSymbol {
@@ -519,11 +3058,46 @@ impl<'env> ModelBuilder<'env> {
         self.env.symbol_pool().make("old")
     }
 
+    /// Validates the usage of `old(..)` in the given specification. Reports an error for each
+    /// `old(..)` which is applied to an immutable reference, and hence does not change. `loc` is
+    /// the location of the specified item. Usage of `old(..)` in conditions not referring to the
+    /// post state, such as `requires`, is reported by `ModuleBuilder::check_condition_is_valid`.
+    pub fn validate_old_usage(&self, spec: &Spec, loc: &Loc) {
+        for cond in spec.conditions.iter().filter(|cond| cond.kind.allows_old()) {
+            self.validate_old_usage_in_condition(cond, loc);
+        }
+        for impl_spec in spec.on_impl.values() {
+            self.validate_old_usage(impl_spec, loc);
+        }
+    }
+
+    fn validate_old_usage_in_condition(&self, cond: &Condition, loc: &Loc) {
+        for exp in cond.all_exps() {
+            exp.visit_pre_order(&mut |e| {
+                if let ExpData::Call(id, Operation::Old, args) = e {
+                    if let Some(ExpData::LocalVar(arg_id, _) | ExpData::Temporary(arg_id, _)) =
+                        args.first().map(|arg| arg.as_ref())
+                    {
+                        if self.env.get_node_type(*arg_id).is_immutable_reference() {
+                            self.env.diag_with_labels(
+                                Severity::Error,
+                                &self.env.get_node_loc(*id),
+                                "`old(..)` applied to an immutable reference",
+                                vec![(loc.clone(), "in specification of this item".to_owned())],
+                            );
+                        }
+                    }
+                }
+                true
+            });
+        }
+    }
+
     /// Returns the name for the pseudo builtin module.
     pub fn builtin_module(&self) -> ModuleName {
         ModuleName::new(
             Address::Numerical(AccountAddress::ZERO),
-            self.env.symbol_pool().make("$$"),
+            self.builtin_module_name,
         )
     }
 
@@ -538,6 +3112,19 @@ impl<'env> ModelBuilder<'env> {
         for decl in &self.intrinsics {
             self.env.intrinsics.add_decl(decl);
         }
+        self.precompute_struct_abilities();
+    }
+
+    /// Stores the abilities of all structs defined so far in the environment, so they need not
+    /// be recomputed on demand, see `GlobalEnv::get_struct_abilities`. These are the abilities
+    /// declared by the struct entries.
+    pub fn precompute_struct_abilities(&mut self) {
+        let struct_abilities = self
+            .struct_table
+            .values()
+            .map(|entry| (entry.module_id.qualified(entry.struct_id), entry.abilities))
+            .collect::<BTreeMap<_, _>>();
+        self.env.struct_abilities.extend(struct_abilities);
     }
 }
 
@@ -550,3 +3137,69 @@ pub(crate) struct LocalVarEntry {
     /// If this a temporary from Move code, this is it's index.
     pub temp_index: Option<usize>,
 }
+
+/// Computes a representation of the structure of an expression, used for comparing function
+/// bodies. Node ids are dropped and the names of locals are replaced by the order of
+/// their first occurrence.
+fn normalized_exp_shape(exp: &Exp) -> Vec<String> {
+    fn local(locals: &mut BTreeMap<Symbol, usize>, sym: Symbol) -> usize {
+        let next = locals.len();
+        *locals.entry(sym).or_insert(next)
+    }
+    fn pattern(locals: &mut BTreeMap<Symbol, usize>, pat: &Pattern) -> String {
+        match pat {
+            Pattern::Var(_, sym) => format!("${}", local(locals, *sym)),
+            Pattern::Wildcard(_) => "_".to_owned(),
+            Pattern::Tuple(_, pats) => {
+                format!("({})", pats.iter().map(|p| pattern(locals, p)).join(", "))
+            },
+            Pattern::Struct(_, id, pats) => format!(
+                "{:?}{{{}}}",
+                id,
+                pats.iter().map(|p| pattern(locals, p)).join(", ")
+            ),
+            Pattern::Error(_) => "error".to_owned(),
+        }
+    }
+    let mut locals = BTreeMap::new();
+    let mut shape = vec![];
+    exp.visit_pre_post(&mut |post, e| {
+        if post {
+            shape.push(")".to_owned());
+            return true;
+        }
+        let token = match e {
+            ExpData::Invalid(_) => "invalid".to_owned(),
+            ExpData::Value(_, val) => format!("value {:?}", val),
+            ExpData::LocalVar(_, sym) => format!("local ${}", local(&mut locals, *sym)),
+            ExpData::Temporary(_, idx) => format!("temp {}", idx),
+            ExpData::Call(_, op, _) => format!("call {:?}", op),
+            ExpData::Invoke(..) => "invoke".to_owned(),
+            ExpData::Lambda(_, pat, _) => format!("lambda {}", pattern(&mut locals, pat)),
+            ExpData::Quant(_, kind, ranges, triggers, cond, _) => format!(
+                "quant {:?} [{}] {} {}",
+                kind,
+                ranges
+                    .iter()
+                    .map(|(pat, _)| pattern(&mut locals, pat))
+                    .join(", "),
+                triggers.len(),
+                cond.is_some()
+            ),
+            ExpData::Block(_, pat, binding, _) => {
+                format!("block {} {}", pattern(&mut locals, pat), binding.is_some())
+            },
+            ExpData::IfElse(..) => "if".to_owned(),
+            ExpData::Return(..) => "return".to_owned(),
+            ExpData::Sequence(..) => "sequence".to_owned(),
+            ExpData::Loop(..) => "loop".to_owned(),
+            ExpData::LoopCont(_, is_continue) => format!("loop_cont {}", is_continue),
+            ExpData::Assign(_, pat, _) => format!("assign {}", pattern(&mut locals, pat)),
+            ExpData::Mutate(..) => "mutate".to_owned(),
+            ExpData::SpecBlock(..) => "spec".to_owned(),
+        };
+        shape.push(token);
+        true
+    });
+    shape
+}
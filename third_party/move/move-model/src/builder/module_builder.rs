@@ -11,9 +11,10 @@ use crate::{
     builder::{
         exp_builder::ExpTranslator,
         model_builder::{
-            ConstEntry, EntryVisibility, FunEntry, LocalVarEntry, ModelBuilder,
+            AnyFunEntry, ConstEntry, EntryVisibility, FunEntry, LocalVarEntry, ModelBuilder,
             SpecOrBuiltinFunEntry,
         },
+        pluralize,
     },
     constant_folder::ConstantFolder,
     exp_rewriter::{ExpRewriter, ExpRewriterFunctions, RewriteTarget},
@@ -90,6 +91,8 @@ pub(crate) struct ModuleBuilder<'env, 'translator> {
     /// Let bindings for the current spec block, characterized by a boolean indicating whether
     /// post state is active and the node id of the original expression of the let.
     pub spec_block_lets: BTreeMap<Symbol, (bool, NodeId)>,
+    /// A transient container for the schemas referenced while analyzing a schema expression.
+    pub referenced_schemas: Vec<QualifiedSymbol>,
 }
 
 /// Represents information about a module already compiled into bytecode by the legacy
@@ -155,6 +158,7 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
             module_spec: Spec::default(),
             spec_block_infos: Default::default(),
             spec_block_lets: BTreeMap::new(),
+            referenced_schemas: vec![],
         }
     }
 
@@ -181,9 +185,37 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
     pub fn translate(
         &mut self,
         loc: Loc,
-        module_def: EA::ModuleDefinition,
+        mut module_def: EA::ModuleDefinition,
         compiled_module: Option<BytecodeModule>,
     ) {
+        // Declarations excluded by the build configuration are removed upfront, so they are
+        // neither analyzed nor entered into the tables. This is the only place they are filtered.
+        let build_config = self.parent.build_config;
+        let is_included = |attrs: &EA::Attributes| {
+            build_config.includes(
+                attrs
+                    .key_cloned_iter()
+                    .map(|(name, _)| name.value.to_string()),
+            )
+        };
+        module_def.structs = module_def
+            .structs
+            .filter_map(|_, def| is_included(&def.attributes).then_some(def));
+        module_def.functions = module_def
+            .functions
+            .filter_map(|_, def| is_included(&def.attributes).then_some(def));
+        module_def.constants = module_def
+            .constants
+            .filter_map(|_, def| is_included(&def.attributes).then_some(def));
+        if let Some(package_name) = &module_def.package_name {
+            let package_name = self.symbol_pool().make(package_name.as_str());
+            self.parent
+                .module_packages
+                .insert(self.module_id, package_name);
+            if !self.parent.env.file_id_is_dep.contains(&loc.file_id()) {
+                self.parent.target_packages.insert(package_name);
+            }
+        }
         self.decl_ana(&module_def, &compiled_module);
         self.def_ana(&module_def, &compiled_module);
         self.collect_spec_block_infos(&module_def);
@@ -420,6 +452,7 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
                 &format!("duplicate declaration of const `{}`", &name.value()),
             )
         }
+        let attributes = self.translate_attributes(&def.attributes);
         let mut et = ExpTranslator::new(self);
         et.set_translate_move_fun();
         let loc = et.to_loc(&def.loc);
@@ -429,6 +462,7 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
             ty,
             value: Value::Bool(false), // dummy value, actual will be assigned in def_ana
             visibility: EntryVisibility::SpecAndImpl,
+            attributes,
         });
     }
 
@@ -482,6 +516,11 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
         et.enter_scope();
         let params = et.analyze_and_add_params(&def.signature.parameters, true);
         let result_type = et.translate_type(&def.signature.return_type);
+        let declared_result_arity = match &def.signature.return_type.value {
+            EA::Type_::Unit => 0,
+            EA::Type_::Multiple(tys) => tys.len(),
+            _ => 1,
+        };
         let kind = if def.entry.is_some() {
             FunctionKind::Entry
         } else if def.inline {
@@ -497,20 +536,24 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
         let is_native = matches!(def.body.value, EA::FunctionBody_::Native);
         let def_loc = et.to_loc(&def.loc);
         let name_loc = et.to_loc(&name.loc());
-        et.parent.parent.define_fun(qsym.clone(), FunEntry {
-            loc: def_loc.clone(),
-            name_loc,
-            module_id: et.parent.module_id,
-            fun_id,
-            visibility,
-            is_native,
-            kind,
-            type_params: type_params.clone(),
-            params: params.clone(),
-            result_type: result_type.clone(),
-            attributes,
-            inline_specs: def.specs.clone(),
-        });
+        et.parent.parent.define_fun(
+            qsym.clone(),
+            FunEntry {
+                loc: def_loc.clone(),
+                name_loc,
+                module_id: et.parent.module_id,
+                fun_id,
+                visibility,
+                is_native,
+                kind,
+                type_params: type_params.clone(),
+                params: params.clone(),
+                result_type: result_type.clone(),
+                attributes,
+                inline_specs: def.specs.clone(),
+            },
+            declared_result_arity,
+        );
     }
 
     fn decl_ana_use_decl(&mut self, use_decl: &PA::UseDecl) {
@@ -544,11 +587,8 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
             PA::LeadingNameAccess_::Name(x) => {
                 let addr_alias = self.symbol_pool().make(x.value.as_str());
                 let addr = Address::Symbolic(addr_alias);
-                if let Some(num) = self.parent.env.resolve_address_alias(addr_alias) {
-                    (addr, Address::Numerical(num))
-                } else {
-                    (addr.clone(), addr)
-                }
+                let resolved_addr = self.parent.canonicalize_address(&addr);
+                (addr, resolved_addr)
             },
             PA::LeadingNameAccess_::AnonymousAddress(num) => {
                 let addr = Address::Numerical(num.into_inner());
@@ -620,17 +660,30 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
         }
     }
 
+    /// Determines whether the attributes contain one with the given name.
+    fn has_attribute(attributes: &EA::Attributes, name: &str) -> bool {
+        attributes.iter().any(|(_, _, attr)| {
+            matches!(
+                &attr.value,
+                EA::Attribute_::Name(n)
+                | EA::Attribute_::Parameterized(n, _)
+                | EA::Attribute_::Assigned(n, _) if n.value.as_str() == name
+            )
+        })
+    }
+
     /// Process any spec block members which introduce global declarations.
     fn decl_ana_spec_block_member(&mut self, member: &EA::SpecBlockMember) {
         use EA::SpecBlockMember_::*;
         let loc = self.parent.env.to_loc(&member.loc);
         match &member.value {
             Function {
+                attributes,
                 uninterpreted,
                 name,
                 signature,
                 ..
-            } => self.decl_ana_spec_fun(&loc, *uninterpreted, name, signature),
+            } => self.decl_ana_spec_fun(&loc, attributes, *uninterpreted, name, signature),
             Variable {
                 is_global: true,
                 name,
@@ -650,10 +703,13 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
     fn decl_ana_spec_fun(
         &mut self,
         loc: &Loc,
+        attributes: &EA::Attributes,
         uninterpreted: bool,
         name: &PA::FunctionName,
         signature: &EA::FunctionSignature,
     ) {
+        // A `#[decreases]` attribute on the function marks it as having a termination hint.
+        let decreases = Self::has_attribute(attributes, "decreases");
         let name = self.symbol_pool().make(&name.0.value);
         let (type_params, params, result_type) = self.decl_ana_signature(signature, false);
 
@@ -671,6 +727,11 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
                 visibility: EntryVisibility::Spec,
             },
         );
+        if decreases {
+            self.parent
+                .decreasing_spec_funs
+                .insert(self.module_id.qualified(fun_id));
+        }
 
         // Add a prototype of the SpecFunDecl to the module build. This
         // will for now have an empty body which we fill in during a 2nd pass.
@@ -1029,6 +1090,7 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
         signature: &EA::FunctionSignature,
     ) {
         let (type_params, params, result_type) = self.decl_ana_signature(signature, true);
+        self.parent.check_distinct_type_params(&type_params);
         let generic_msg = "provided function signature must match function declaration";
         if !fun_decl.type_params.eq_ignoring_loc(&type_params) {
             self.parent
@@ -1065,6 +1127,7 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
         };
         let name = qsym.symbol;
         let const_name = ConstantName(self.symbol_pool().string(name).to_string().into());
+        let error_count = self.parent.env.error_count();
         let value = if let Some(BytecodeModule {
             compiled_module,
             source_map,
@@ -1084,7 +1147,8 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
             et.set_translate_move_fun();
             et.translate_from_move_value(&loc, &ty, &move_value)
         } else {
-            // Type check the constant.
+            // Type check the constant. If enabled, the range of the value is validated below,
+            // in addition to the range of the literals checked here.
             let mut et = ExpTranslator::new(self);
             et.set_translate_move_fun();
             let exp = et.translate_exp(&def.value, &ty).into_exp();
@@ -1101,7 +1165,7 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
                 ok = false;
             }
             if !ty.is_valid_for_constant() {
-                let reasons = vec![(loc, Type::describe_valid_for_constant().to_owned())];
+                let reasons = vec![(loc.clone(), Type::describe_valid_for_constant().to_owned())];
                 self.parent.env.diag_with_labels(
                     Severity::Error,
                     &self.parent.env.get_node_loc(exp.node_id()),
@@ -1124,11 +1188,22 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
                 Value::Bool(false)
             }
         };
+        // Only validate the range if no error has been reported for the constant already.
+        let value = if self.parent.env.error_count() == error_count {
+            self.parent.validated_const_value(&loc, &ty, value)
+        } else {
+            value
+        };
         self.parent
             .const_table
             .get_mut(&qsym)
             .expect("constant declared")
             .value = value;
+        self.parent.check_reserved_error_code(
+            self.module_id,
+            &qsym,
+            &self.parent.const_table[&qsym],
+        );
     }
 }
 
@@ -1166,19 +1241,31 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
                     ),
                 ),
                 Ability::Store => {
-                    let mut abilities = Vec::new();
-                    if struct_abilities.has_store() {
-                        abilities.push("store");
-                    }
-                    if struct_abilities.has_key() {
-                        abilities.push("key");
-                    }
+                    let abilities =
+                        self.parent
+                            .ability_names(struct_abilities.intersect(
+                                AbilitySet::singleton(Ability::Store).add(Ability::Key),
+                            ));
+                    // Name the nested type which lacks `store`, if any.
+                    let nested = self
+                        .parent
+                        .find_type_lacking_ability(field_ty, Ability::Store)
+                        .filter(|ty| *ty != field_ty)
+                        .map(|ty| {
+                            format!(
+                                ", but `{}` in `{}` does not have store",
+                                self.parent.display_type_full(ty),
+                                self.parent.display_type_full(field_ty)
+                            )
+                        })
+                        .unwrap_or_default();
                     self.parent.error(
                         field_ty_loc,
                         &format!(
-                            "field must have store ability because {} is declared with {}",
+                            "field must have store ability because {} is declared with {}{}",
                             struct_name.display_simple(self.parent.env),
-                            abilities.join(" + ")
+                            abilities.join(" + "),
+                            nested
                         ),
                     );
                 },
@@ -1229,8 +1316,7 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
             .get_mut(&qsym)
             .expect("struct invalid")
             .fields = fields;
-        self.parent
-            .ability_check_struct_def(self.parent.struct_table.get(&qsym).expect("struct invalid"));
+        self.parent.ability_check_or_defer_struct_def(&qsym);
     }
 
     /// The name of a dummy field the legacy Move compilers adds to zero-arity structs.
@@ -1579,7 +1665,9 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
                     .get(name)
                     .expect("invalid spec block context")
                     .clone();
-                let mut et = ExpTranslator::new_with_old(self, allows_old);
+                // Usage of `old(..)` in conditions not referring to the post state is reported by
+                // `check_condition_is_valid`, other misuse by `ModelBuilder::validate_old_usage`.
+                let mut et = ExpTranslator::new_with_old(self, true);
                 for (pos, TypeParameter(name, _, loc)) in entry.type_params.iter().enumerate() {
                     et.define_type_param(
                         loc,
@@ -2034,7 +2122,7 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
         let mut et = self.exp_translator_for_context(loc, context, &kind);
         let (translated, translated_additional) = match kind {
             ConditionKind::AbortsIf => (
-                et.translate_exp(exp, &expected_type).into_exp(),
+                Self::translate_predicate(&mut et, exp, &expected_type).into_exp(),
                 additional_exps
                     .iter()
                     .map(|code| {
@@ -2078,7 +2166,10 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
             },
             ConditionKind::Axiom(ref type_params) => {
                 et.define_type_params(loc, &TypeParameter::from_symbols(type_params.iter()), false);
-                (et.translate_exp(exp, &expected_type).into_exp(), vec![])
+                (
+                    Self::translate_predicate(&mut et, exp, &expected_type).into_exp(),
+                    vec![],
+                )
             },
             _ => {
                 if !additional_exps.is_empty() {
@@ -2087,7 +2178,10 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
                           "additional expressions only allowed with `aborts_if`, `aborts_with`, `modifies`, or `emits`",
                       );
                 }
-                (et.translate_exp(exp, &expected_type).into_exp(), vec![])
+                (
+                    Self::translate_predicate(&mut et, exp, &expected_type).into_exp(),
+                    vec![],
+                )
             },
         };
         et.finalize_types();
@@ -2116,6 +2210,54 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
         BOOL_TYPE.clone()
     }
 
+    /// Translates the predicate of a condition. If the predicate is a call of a function which
+    /// cannot return `bool`, as all the candidates the call may resolve to have a different,
+    /// non-generic result type, an error pointing at the misuse is reported instead of a type
+    /// mismatch. The candidates are determined as in call translation.
+    fn translate_predicate(et: &mut ExpTranslator, exp: &EA::Exp, expected_type: &Type) -> ExpData {
+        if let EA::Exp_::Call(maccess, false, _, _) = &exp.value {
+            let (module_name, name) = et.parent.module_access_to_parts(maccess);
+            let is_local = module_name.is_none() && et.lookup_local(name, false).is_some();
+            let cands = if is_local {
+                vec![]
+            } else {
+                et.call_candidates(&module_name, name, None)
+            };
+            let cannot_return_bool = cands.iter().all(|cand| {
+                let (_, _, result_type) = cand.get_signature();
+                let mut is_generic = false;
+                result_type.visit(&mut |t| is_generic |= matches!(t, Type::TypeParameter(_)));
+                !is_generic && result_type != &BOOL_TYPE
+            });
+            let result_type = cands
+                .first()
+                .filter(|_| cannot_return_bool)
+                .map(|cand| cand.get_signature().2.clone());
+            if let Some(result_type) = result_type {
+                let loc = et.to_loc(&exp.loc);
+                let kind = match cands.first() {
+                    Some(AnyFunEntry::SpecOrBuiltin(entry))
+                        if matches!(entry.oper, Operation::SpecFunction(..)) =>
+                    {
+                        "spec function"
+                    },
+                    Some(AnyFunEntry::UserFun(_)) => "function",
+                    _ => "builtin function",
+                };
+                let msg = format!(
+                    "expected a boolean predicate, but {} `{}` returns `{}`",
+                    kind,
+                    et.display_call_target(&module_name, name),
+                    result_type.display(&et.parent.parent.type_display_context())
+                );
+                et.error(&loc, &msg);
+                // Translate without expectation to avoid a redundant type mismatch error.
+                return et.translate_exp_free(exp).1;
+            }
+        }
+        et.translate_exp(exp, expected_type)
+    }
+
     /// Convert a condition kind from AST into the ConditionKind known by the move model.
     fn convert_condition_kind(
         &mut self,
@@ -2253,7 +2395,15 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
                 let translated =
                     et.translate_seq(&loc, seq, &result_type, &ErrorMessageContext::Return);
                 et.finalize_types();
-                self.spec_funs[self.spec_fun_index].body = Some(translated.into_exp());
+                let body = translated.into_exp();
+                let name = self.qualified_by_module(self.spec_funs[self.spec_fun_index].name);
+                let oper = Operation::SpecFunction(
+                    self.module_id,
+                    SpecFunId::new(self.spec_fun_index),
+                    None,
+                );
+                self.parent.check_spec_fun_termination(&name, &oper, &body);
+                self.spec_funs[self.spec_fun_index].body = Some(body);
             },
             EA::FunctionBody_::Native => {
                 if !uninterpreted {
@@ -2703,6 +2853,7 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
 
         // Remove schema from unused table since it is used in an expression
         self.parent.unused_schema_set.remove(&schema_name);
+        self.referenced_schemas.push(schema_name.clone());
 
         // We need to temporarily detach the schema entry from the parent table because of
         // borrowing problems, as we need to traverse it while at the same time mutate self.
@@ -2726,8 +2877,10 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
             self.parent.error(
                 loc,
                 &format!(
-                    "wrong number of type arguments (expected {}, got {})",
+                    "schema `{}` expects {} type {}, got {}",
+                    schema_name.display(self.parent.env),
                     schema_entry.type_params.len(),
+                    pluralize("argument", schema_entry.type_params.len()),
                     type_arguments.len()
                 ),
             );
@@ -3027,6 +3180,7 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
             .iter()
             .map(|(n, _, loc)| TypeParameter(*n, TypeParameterKind::default(), loc.clone()))
             .collect::<Vec<_>>();
+        let outer_referenced_schemas = std::mem::take(&mut self.referenced_schemas);
         self.def_ana_schema_exp(
             if let Some(type_params) = alt_context_type_params {
                 type_params
@@ -3039,6 +3193,18 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
             &PropertyBag::default(),
             exp,
         );
+        let included_schemas =
+            std::mem::replace(&mut self.referenced_schemas, outer_referenced_schemas);
+
+        // Record the functions the schemas are applied to.
+        if let SpecBlockContext::Function(name)
+        | SpecBlockContext::FunctionCode(name, _)
+        | SpecBlockContext::FunctionCodeV2(name, _) = context
+        {
+            for schema_name in included_schemas {
+                self.parent.record_schema_application(schema_name, name);
+            }
+        }
 
         // Write the conditions to the context item.
         self.add_conditions_to_context(
@@ -3090,13 +3256,14 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
                 let type_params = {
                     let mut et = ExpTranslator::new(self);
                     let ability_set = EA::AbilitySet::empty();
-                    et.analyze_and_add_type_params(
+                    let type_params = et.analyze_and_add_type_params(
                         matched
                             .value
                             .type_parameters
                             .iter()
                             .map(|(n, _)| (n, &ability_set, false)),
                     );
+                    et.parent.parent.check_distinct_type_params(&type_params);
                     et.get_type_params()
                 };
                 // Create a property marking this as injected.
@@ -3373,6 +3540,7 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
             }
             // New function
             let spec = self.fun_specs.remove(&name.symbol).unwrap_or_default();
+            self.parent.validate_old_usage(&spec, &entry.name_loc);
             let def = self.fun_defs.remove(&name.symbol);
             let called_funs = Some(def.as_ref().map(|e| e.called_funs()).unwrap_or_default());
             let access_specifiers = self.fun_access_specifiers.remove(&name.symbol);
@@ -3410,6 +3578,7 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
                 value,
                 ty,
                 visibility: _,
+                attributes: _,
             } = const_entry.clone();
             let data = NamedConstantData {
                 name: name.symbol,
@@ -24,6 +24,11 @@ pub struct IntrinsicDecl {
 }
 
 impl IntrinsicDecl {
+    /// Returns the Move struct which is mapped to the intrinsic type.
+    pub fn move_type(&self) -> QualifiedId<StructId> {
+        self.move_type
+    }
+
     pub fn get_fun_triple(&self, env: &GlobalEnv, name: &str) -> Option<(Address, String, String)> {
         let symbol_pool = env.symbol_pool();
         let sym = symbol_pool.make(name);
@@ -125,7 +130,8 @@ pub(crate) fn process_intrinsic_declaration(
     // construct the pack
     populate_intrinsic_decl(builder, loc, associated_funs, props, &mut decl);
 
-    // add the decl back
+    // add the decl back, the struct is opaque in specifications from now on
+    builder.parent.abstract_spec_types.insert(move_type);
     builder.parent.intrinsics.push(decl);
 }
 
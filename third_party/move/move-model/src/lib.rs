@@ -6,7 +6,10 @@
 
 use crate::{
     ast::ModuleName,
-    builder::{model_builder::ModelBuilder, module_builder::BytecodeModule},
+    builder::{
+        model_builder::{BuildConfig, ModelBuilder},
+        module_builder::BytecodeModule,
+    },
     model::{FunId, GlobalEnv, Loc, ModuleId, StructId},
     options::ModelBuilderOptions,
 };
@@ -355,7 +358,16 @@ pub fn run_model_builder_with_options_and_compilation_flags<
 }
 
 fn run_move_checker(env: &mut GlobalEnv, program: E::Program) {
+    let options = env
+        .get_extension::<ModelBuilderOptions>()
+        .unwrap_or_default();
     let mut builder = ModelBuilder::new(env);
+    // Conditionally compiled declarations are only excluded when building from source, since
+    // compiled modules already reflect the configuration they were compiled with.
+    builder.build_config = BuildConfig {
+        include_test: !options.exclude_test_code,
+        include_verify_only: !options.exclude_verify_only_code,
+    };
     for (module_count, (module_id, module_def)) in program
         .modules
         .into_iter()
@@ -386,6 +398,59 @@ fn run_move_checker(env: &mut GlobalEnv, program: E::Program) {
         let module_def = expansion_script_to_module(script_def);
         module_translator.translate(loc, module_def, None);
     }
+    // Run ability checks which have been deferred until all modules are known.
+    builder.run_deferred_ability_checks();
+    builder.precompute_struct_abilities();
+    builder.check_schema_vars();
+    builder.check_abstract_spec_type_usage();
+    if options.warn_layout_duplicate_structs {
+        builder.warn_layout_duplicate_structs();
+    }
+    if options.warn_opaque_structs {
+        builder.warn_opaque_structs();
+    }
+    if let Some(threshold) = options.max_struct_fields {
+        builder.warn_wide_structs(threshold);
+    }
+    if let Some(limit) = options.max_instantiation_depth {
+        builder.warn_deep_instantiations(limit);
+    }
+    if options.check_public_api_types {
+        builder.check_public_api_types();
+    }
+    if options.check_attributes {
+        builder.check_attributes();
+    }
+    if options.warn_unused_spec_funs {
+        builder.warn_unused_spec_funs();
+    }
+    if options.report_schema_applications {
+        builder.report_schema_applications();
+    }
+    if options.warn_unspecified_public_funs {
+        builder.warn_unspecified_public_funs();
+    }
+    if options.warn_unknown_abort_codes {
+        builder.warn_unknown_abort_codes();
+    }
+    if options.warn_zero_constants {
+        builder.warn_zero_constants();
+    }
+    if options.warn_unused_imports {
+        builder.warn_unused_imports();
+    }
+    if options.warn_cloned_functions {
+        builder.warn_cloned_functions();
+    }
+    if options.warn_recursive_functions {
+        builder.warn_recursive_functions();
+    }
+    if options.warn_unused_params {
+        builder.warn_unused_params();
+    }
+    if options.check_type_param_abilities {
+        builder.check_type_param_abilities();
+    }
     // Perform any remaining friend-declaration checks and update friend module id information.
     check_and_update_friend_info(builder);
 }
@@ -723,11 +788,56 @@ fn run_spec_checker(env: &mut GlobalEnv, units: Vec<AnnotatedCompiledUnit>, mut
         module_translator.translate(loc, expanded_module, Some(compiled_module));
     }
 
+    // Run ability checks which have been deferred until all modules are known.
+    builder.run_deferred_ability_checks();
+
+    // Check schema variables and the usage of abstract spec types.
+    builder.check_schema_vars();
+    builder.check_abstract_spec_type_usage();
+
     // Populate GlobalEnv with model-level information
     builder.populate_env();
 
     // After all specs have been processed, warn about any unused schemas.
     builder.warn_unused_schemas();
+
+    let options = builder
+        .env
+        .get_extension::<ModelBuilderOptions>()
+        .unwrap_or_default();
+    if options.warn_layout_duplicate_structs {
+        builder.warn_layout_duplicate_structs();
+    }
+    if options.warn_opaque_structs {
+        builder.warn_opaque_structs();
+    }
+    if let Some(threshold) = options.max_struct_fields {
+        builder.warn_wide_structs(threshold);
+    }
+    if let Some(limit) = options.max_instantiation_depth {
+        builder.warn_deep_instantiations(limit);
+    }
+    if options.check_public_api_types {
+        builder.check_public_api_types();
+    }
+    if options.check_attributes {
+        builder.check_attributes();
+    }
+    if options.warn_unused_spec_funs {
+        builder.warn_unused_spec_funs();
+    }
+    if options.report_schema_applications {
+        builder.report_schema_applications();
+    }
+    if options.warn_unspecified_public_funs {
+        builder.warn_unspecified_public_funs();
+    }
+    if options.warn_unknown_abort_codes {
+        builder.warn_unknown_abort_codes();
+    }
+    if options.warn_zero_constants {
+        builder.warn_zero_constants();
+    }
 }
 
 fn retrospective_lambda_lifting(
@@ -554,6 +554,9 @@ pub struct GlobalEnv {
     /// are represented without type instantiation because we assume the backend can handle
     /// generics in the expression language.
     pub(crate) used_spec_funs: BTreeSet<QualifiedId<SpecFunId>>,
+    /// A cache of the abilities each struct may have, assuming all type parameters have all
+    /// abilities. This is precomputed when the model builder populates the environment.
+    pub(crate) struct_abilities: BTreeMap<QualifiedId<StructId>, AbilitySet>,
     /// An annotation of all intrinsic declarations
     pub(crate) intrinsics: IntrinsicsAnnotation,
     /// A type-indexed container for storing extension data in the environment.
@@ -616,6 +619,7 @@ impl GlobalEnv {
             global_invariants: Default::default(),
             global_invariants_for_memory: Default::default(),
             used_spec_funs: BTreeSet::new(),
+            struct_abilities: BTreeMap::new(),
             intrinsics: Default::default(),
             extensions: Default::default(),
             stdlib_address: None,
@@ -827,6 +831,24 @@ impl GlobalEnv {
         self.diag_with_notes(Severity::Error, loc, msg, notes)
     }
 
+    /// Adds an error with a stable code, as in `error[E0402]: ...`, to this environment. Tools
+    /// can use the code to categorize or suppress diagnostics independent of their message.
+    pub fn error_with_code(&self, loc: &Loc, code: &str, msg: &str) {
+        self.error_with_code_and_labels(loc, code, msg, vec![])
+    }
+
+    /// Adds an error with a stable code to this environment, with labels.
+    pub fn error_with_code_and_labels(
+        &self,
+        loc: &Loc,
+        code: &str,
+        msg: &str,
+        labels: Vec<(Loc, String)>,
+    ) {
+        let diag = Self::mk_diag_with_labels(Severity::Error, loc, msg, "", labels).with_code(code);
+        self.add_diag(diag);
+    }
+
     /// Adds an error to this environment, with notes.
     pub fn error_with_labels(&self, loc: &Loc, msg: &str, labels: Vec<(Loc, String)>) {
         self.diag_with_labels(Severity::Error, loc, msg, labels)
@@ -888,6 +910,18 @@ impl GlobalEnv {
         primary: &str,
         labels: Vec<(Loc, String)>,
     ) {
+        let diag = Self::mk_diag_with_labels(severity, loc, msg, primary, labels);
+        self.add_diag(diag);
+    }
+
+    /// Creates a diagnostic of given severity, with primary and secondary labels.
+    fn mk_diag_with_labels(
+        severity: Severity,
+        loc: &Loc,
+        msg: &str,
+        primary: &str,
+        labels: Vec<(Loc, String)>,
+    ) -> Diagnostic<FileId> {
         let new_msg = Self::add_backtrace(msg, severity == Severity::Bug);
 
         // primary
@@ -907,8 +941,7 @@ impl GlobalEnv {
                 expanded_labels
             })
             .concat();
-        let diag = diag.with_labels(labels);
-        self.add_diag(diag);
+        diag.with_labels(labels)
     }
 
     /// Checks whether any of the diagnostics contains string.
@@ -919,6 +952,18 @@ impl GlobalEnv {
             .any(|(d, _)| d.message.contains(pattern))
     }
 
+    /// Checks whether any of the diagnostics has its primary label at the given location.
+    pub fn has_diag_at(&self, loc: &Loc) -> bool {
+        let range: std::ops::Range<usize> = loc.span.into();
+        self.diags.borrow().iter().any(|(d, _)| {
+            d.labels.iter().any(|label| {
+                label.style == LabelStyle::Primary
+                    && label.file_id == loc.file_id
+                    && label.range == range
+            })
+        })
+    }
+
     /// Clear all accumulated diagnosis.
     pub fn clear_diag(&self) {
         self.diags.borrow_mut().clear();
@@ -1312,6 +1357,12 @@ impl GlobalEnv {
         )
     }
 
+    /// Returns the precomputed abilities the given struct may have, if all its type parameters
+    /// have all abilities. Returns `None` if the abilities have not been computed yet.
+    pub fn get_struct_abilities(&self, id: QualifiedId<StructId>) -> Option<AbilitySet> {
+        self.struct_abilities.get(&id).copied()
+    }
+
     /// Returns associated intrinsics.
     pub fn get_intrinsics(&self) -> &IntrinsicsAnnotation {
         &self.intrinsics
@@ -1826,7 +1877,9 @@ impl GlobalEnv {
                 id: sid,
             };
             let struct_env = self.get_struct(qid);
-            let struct_abilities = struct_env.get_abilities();
+            let struct_abilities = self
+                .get_struct_abilities(qid)
+                .unwrap_or_else(|| struct_env.get_abilities());
             let ty_param_kinds = struct_env
                 .get_type_parameters()
                 .iter()
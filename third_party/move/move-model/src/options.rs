@@ -19,4 +19,119 @@ pub struct ModelBuilderOptions {
     /// Ignore the "opaque" pragma on all function specs when possible. The opaque can be ignored
     /// as long as the function spec has no property marked as `[concrete]` or `[abstract]`.
     pub ignore_pragma_opaque_when_possible: bool,
+
+    /// Defer the ability checks of struct definitions until all modules have been added to the
+    /// model, instead of running them as each struct is analyzed. As all usages of a struct are
+    /// known then, notes are generated about structs lacking abilities required by their usage.
+    pub defer_ability_checks: bool,
+
+    /// Generate notes about structs in target modules which have the same field layout but
+    /// different abilities.
+    pub warn_layout_duplicate_structs: bool,
+
+    /// Generate notes about structs in target modules which are not returned by any public
+    /// function, hence values of them can only be obtained within their module.
+    pub warn_opaque_structs: bool,
+
+    /// The number of fields a struct in a target module may have before a note is generated.
+    /// By default there is no limit.
+    pub max_struct_fields: Option<usize>,
+
+    /// The nesting depth of generic struct instantiations, as in `A<B<C>>` of depth 3, which
+    /// the types declared in a target module may have before a note is generated. By default
+    /// there is no limit.
+    pub max_instantiation_depth: Option<usize>,
+
+    /// Generate notes about public functions whose signature exposes structs which are internal
+    /// to another package.
+    pub check_public_api_types: bool,
+
+    /// Generate notes about functions in target modules which have identical bodies.
+    pub warn_cloned_functions: bool,
+
+    /// Generate notes about functions in target modules which call themselves, directly or via
+    /// other functions. This only applies when compiling via the model.
+    pub warn_recursive_functions: bool,
+
+    /// Generate notes about parameters of functions in target modules which are not used in
+    /// the function body. This only applies when compiling via the model.
+    pub warn_unused_params: bool,
+
+    /// Check that type parameters used with global storage operations in function bodies are
+    /// declared with the `key` ability.
+    pub check_type_param_abilities: bool,
+
+    /// Check the attributes of structs and functions against the schemas of known attributes.
+    pub check_attributes: bool,
+
+    /// Generate notes about spec functions in target modules which are not used by any
+    /// specification.
+    pub warn_unused_spec_funs: bool,
+
+    /// Generate notes about schemas in target modules which are included by the specs of
+    /// functions, listing these functions.
+    pub report_schema_applications: bool,
+
+    /// Generate notes about public functions in target modules which have no specification,
+    /// neither as a spec block for the function nor inline in its body.
+    pub warn_unspecified_public_funs: bool,
+
+    /// Generate notes about numeric abort codes in `aborts_with` conditions of functions in
+    /// target modules which are not the value of any constant of the module.
+    pub warn_unknown_abort_codes: bool,
+
+    /// Generate notes about integer constants in target modules whose value is zero, which are
+    /// often redundant with the default value of their type.
+    pub warn_zero_constants: bool,
+
+    /// Generate notes about `use` declarations in target modules which import a module that is
+    /// never referenced. Only effective with `compile_via_model`.
+    pub warn_unused_imports: bool,
+
+    /// Generate notes about directly recursive spec functions which have no `#[decreases]`
+    /// termination hint.
+    pub warn_unproven_spec_fun_termination: bool,
+
+    /// The depth up to which a generic struct may be nested in its own type arguments in a
+    /// field type, as in `A<A<A<u64>>>`, before a note is generated. By default there is no
+    /// limit.
+    pub max_self_instantiation_depth: Option<usize>,
+
+    /// Check that the values of integer constants are within the range of their type.
+    pub check_const_ranges: bool,
+
+    /// The maximal number of type parameters a struct or function may declare before a note is
+    /// generated. By default there is no limit.
+    pub max_type_params: Option<usize>,
+
+    /// Report an error instead of a note if `max_type_params` is exceeded.
+    pub strict_max_type_params: bool,
+
+    /// Generate notes about resources, i.e. structs with the `key` ability, which are declared
+    /// in modules at the zero address or in the builtin module.
+    pub check_resource_addresses: bool,
+
+    /// Generate notes about struct fields in target modules whose type is `u128` or `u256`,
+    /// suggesting to review whether a smaller integer type suffices.
+    pub warn_wide_integer_fields: bool,
+
+    /// Generate notes about functions, structs, or constants declared in the same module whose
+    /// names differ only in case.
+    pub warn_case_collisions: bool,
+
+    /// Exclude declarations marked with `#[test]` or `#[test_only]` from the model. This only
+    /// applies when compiling via the model.
+    pub exclude_test_code: bool,
+
+    /// Exclude declarations marked with `#[verify_only]` from the model. This only applies
+    /// when compiling via the model.
+    pub exclude_verify_only_code: bool,
+
+    /// The name of the pseudo module in which builtin functions and operators are declared.
+    /// Defaults to `$$` if not set.
+    pub builtin_module_name: Option<String>,
+
+    /// Inclusive ranges of error codes reserved by a framework. Generate notes about integer
+    /// constants in target modules whose value lies in one of these ranges.
+    pub reserved_error_code_ranges: Vec<(u64, u64)>,
 }
@@ -2486,16 +2486,25 @@ pub fn instantiate_abilities(
     }
 }
 
-/// Checks whether the given type is a phantom type parameter
+/// Checks whether the given type is a phantom type parameter, or a non-empty tuple or function
+/// type built only from phantom type parameters
 /// `ty_param_kinds` specifies the abilities and phantomness of the type parameters
 pub fn is_phantom_type_arg<F>(ty_param_kinds: F, ty: &Type) -> bool
 where
-    F: Fn(u16) -> TypeParameterKind,
+    F: Fn(u16) -> TypeParameterKind + Copy,
 {
-    if let Type::TypeParameter(i) = ty {
-        ty_param_kinds(*i).is_phantom
-    } else {
-        false
+    match ty {
+        Type::TypeParameter(i) => ty_param_kinds(*i).is_phantom,
+        Type::Tuple(elems) => {
+            !elems.is_empty()
+                && elems
+                    .iter()
+                    .all(|elem| is_phantom_type_arg(ty_param_kinds, elem))
+        },
+        Type::Fun(args, result) => {
+            is_phantom_type_arg(ty_param_kinds, args) && is_phantom_type_arg(ty_param_kinds, result)
+        },
+        _ => false,
     }
 }
 
@@ -2534,7 +2543,7 @@ pub fn check_struct_inst<F, G, H>(
 where
     F: Fn(u16) -> TypeParameterKind + Copy,
     G: Fn(ModuleId, StructId) -> (Vec<TypeParameterKind>, AbilitySet) + Copy,
-    H: Fn(&Loc, &Type, &str) + Copy,
+    H: Fn(&Loc, &Type, &TypeArgError) + Copy,
 {
     let (ty_params, struct_abilities) = get_struct_sig(mid, sid);
     let ty_args_abilities_meet = ty_args
@@ -2573,6 +2582,24 @@ where
     instantiate_abilities(struct_abilities, ty_args_abilities_meet)
 }
 
+/// An error in the instantiation of a type parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeArgError {
+    /// the type argument lacks the given abilities required by the type parameter
+    MissingAbilities(AbilitySet),
+    /// a phantom type parameter is passed to a non-phantom position
+    NotPhantomPosition,
+}
+
+impl fmt::Display for TypeArgError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeArgError::MissingAbilities(missing) => write!(f, "missing ability `{}`", missing),
+            TypeArgError::NotPhantomPosition => write!(f, "not a phantom position"),
+        }
+    }
+}
+
 /// check ability constraints on the type param
 pub fn check_type_arg_abilities<F, H>(
     get_ty_param_kinds: F,
@@ -2584,15 +2611,15 @@ pub fn check_type_arg_abilities<F, H>(
     on_err: H,
 ) where
     F: Fn(u16) -> TypeParameterKind + Copy,
-    H: Fn(&Loc, &Type, &str) + Copy,
+    H: Fn(&Loc, &Type, &TypeArgError) + Copy,
 {
     if !required_abilities.is_subset(given_abilities) {
         let missing = required_abilities.setminus(given_abilities);
-        on_err(loc, ty_arg, &format!("missing ability `{}`", missing))
+        on_err(loc, ty_arg, &TypeArgError::MissingAbilities(missing))
     }
     // check phantomness of the type param
     if !is_phantom_position && is_phantom_type_arg(get_ty_param_kinds, ty_arg) {
-        on_err(loc, ty_arg, "not a phantom position")
+        on_err(loc, ty_arg, &TypeArgError::NotPhantomPosition)
     }
 }
 
@@ -2613,7 +2640,7 @@ pub fn infer_abilities_opt_check<F, G, H>(
 where
     F: Fn(u16) -> TypeParameterKind + Copy,
     G: Fn(ModuleId, StructId) -> (Vec<TypeParameterKind>, AbilitySet) + Copy,
-    H: Fn(&Loc, &Type, &str) + Copy,
+    H: Fn(&Loc, &Type, &TypeArgError) + Copy,
 {
     match ty {
         Type::Primitive(p) => match p {
@@ -2675,7 +2702,7 @@ pub fn infer_and_check_abilities<F, G, H>(
 where
     F: Fn(u16) -> TypeParameterKind + Copy,
     G: Fn(ModuleId, StructId) -> (Vec<TypeParameterKind>, AbilitySet) + Copy,
-    H: Fn(&Loc, &Type, &str) + Copy,
+    H: Fn(&Loc, &Type, &TypeArgError) + Copy,
 {
     infer_abilities_opt_check(ty, get_ty_param_kinds, get_struct_sig, Some((loc, on_err)))
 }
@@ -2692,6 +2719,6 @@ where
         ty,
         get_ty_param_kinds,
         get_struct_sig,
-        None::<(&Loc, fn(&Loc, &Type, &str))>,
+        None::<(&Loc, fn(&Loc, &Type, &TypeArgError))>,
     )
 }
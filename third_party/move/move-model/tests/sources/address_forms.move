@@ -0,0 +1,15 @@
+module 0x42::M {
+    struct S has drop { x: u64 }
+
+    public fun f(): S { S { x: 1 } }
+}
+
+module 0x42::N {
+    use 0x0000000000000000000000000000000000000000000000000000000000000042::M;
+
+    fun g(s: M::S): M::S { s }
+
+    spec g {
+        ensures result == s;
+    }
+}
@@ -0,0 +1,12 @@
+module 0x42::M {
+    struct R has key { v: vector<u64> }
+
+    fun contains(a: address): bool {
+        exists<R>(a)
+    }
+
+    spec contains {
+        ensures result == exists<R>(a);
+        ensures result ==> len(global<R>(a).v) >= 0;
+    }
+}
@@ -0,0 +1,9 @@
+module 0x42::M {
+    fun len(a: u64, b: u64): u64 {
+        a + b
+    }
+
+    fun concat(v: vector<u64>, _w: vector<u64>): vector<u64> {
+        v
+    }
+}
@@ -0,0 +1,12 @@
+module 0x42::M {
+    #[deprecated]
+    struct S { x: u64 }
+    #[deprecated(note = b"use `S` instead")]
+    struct T { x: u64 }
+    #[deprecated(reason = b"obsolete")]
+    fun f() {}
+    #[deprecated = b"obsolete"]
+    fun g() {}
+    #[unknown]
+    fun h() {}
+}
@@ -0,0 +1,7 @@
+module 0x42::M {
+    const SMALL: u8 = 255;
+
+    const TOO_LARGE: u8 = 256;
+
+    const LARGE: u256 = 115792089237316195423570985008687907853269984665640564039457584007913129639935;
+}
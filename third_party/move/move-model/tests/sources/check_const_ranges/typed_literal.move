@@ -0,0 +1,3 @@
+module 0x42::M {
+    const SUM: u8 = 300u8 + 0;
+}
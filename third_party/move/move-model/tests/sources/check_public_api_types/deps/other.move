@@ -0,0 +1,9 @@
+module 0x43::Other {
+    struct Handle has drop { id: u64 }
+
+    struct Token has drop, store { id: u64 }
+
+    struct Capability has drop { id: u64 }
+
+    public fun capability(): Capability { Capability { id: 0 } }
+}
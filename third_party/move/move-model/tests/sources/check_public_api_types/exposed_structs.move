@@ -0,0 +1,16 @@
+module 0x42::Types {
+    struct Local has drop { id: u64 }
+}
+
+module 0x42::M {
+    use 0x42::Types::Local;
+    use 0x43::Other::{Capability, Handle, Token};
+
+    public fun local(x: Local): Local { x }
+
+    public fun token(x: Token): Token { x }
+
+    public fun handle(x: Handle): Handle { x }
+
+    public fun uses_capability(_cap: &Capability): u64 { 0 }
+}
@@ -0,0 +1,8 @@
+module 0x42::M {
+    struct Balance has key { value: u64 }
+}
+
+module 0x0::Z {
+    struct Balance has key { value: u64 }
+    struct Info has store { value: u64 }
+}
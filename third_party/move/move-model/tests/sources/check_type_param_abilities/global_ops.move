@@ -0,0 +1,9 @@
+module 0x42::M {
+    fun publish<T: store>(s: &signer, x: T) {
+        move_to<T>(s, x)
+    }
+
+    fun check<T: key>(a: address): bool {
+        exists<T>(a)
+    }
+}
@@ -0,0 +1,20 @@
+module 0x42::N {
+    struct Box<T> has key { x: T }
+    struct Tag<phantom T> has key {}
+
+    fun publish<T: drop>(s: &signer, x: T) {
+        move_to(s, Box<T> { x })
+    }
+
+    fun tagged<T>(a: address): bool {
+        exists<Tag<T>>(a)
+    }
+
+    fun nested<T: store>(a: address): bool {
+        exists<Box<vector<T>>>(a)
+    }
+
+    fun nested_missing<T: copy>(a: address): bool {
+        exists<Box<vector<T>>>(a)
+    }
+}
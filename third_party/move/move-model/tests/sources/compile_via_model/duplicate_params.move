@@ -0,0 +1,3 @@
+module 0x42::M {
+    fun f(x: u64, x: u64) {}
+}
@@ -0,0 +1,9 @@
+module 0x42::M {
+    struct S<T, T> { f: T }
+
+    fun foo<T, T>() {}
+
+    spec module {
+        fun bar<T, T>(): bool { true }
+    }
+}
@@ -0,0 +1,6 @@
+module 0x42::M {
+    // Function types have `copy` and `drop`, so they can be stored in such a struct.
+    struct Callback has copy, drop {
+        f: |u64|u64,
+    }
+}
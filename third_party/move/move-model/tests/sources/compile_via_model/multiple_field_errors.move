@@ -0,0 +1,11 @@
+module 0x42::M {
+    struct NoAbilities {}
+
+    struct NeedsCopy<T: copy> { x: T }
+
+    // Both fields are reported, not just the first one.
+    struct Holder {
+        f: NeedsCopy<NoAbilities>,
+        g: NeedsCopy<signer>,
+    }
+}
@@ -0,0 +1,10 @@
+module 0x42::M {
+    struct Other<U> has drop {
+        u: U,
+    }
+
+    // Not reported, as `S` and `Other` do not form a recursive group.
+    struct S<phantom T> has drop {
+        o: Other<vector<T>>,
+    }
+}
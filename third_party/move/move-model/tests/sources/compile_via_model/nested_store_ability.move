@@ -0,0 +1,15 @@
+module 0x42::M {
+    struct Table<phantom K: copy + drop, V> has store {
+        values: vector<V>,
+    }
+
+    struct NonStorable has drop {}
+
+    struct Outer has store {
+        t: Table<u64, NonStorable>,
+    }
+
+    struct Nested has store {
+        t: Table<u64, vector<NonStorable>>,
+    }
+}
@@ -0,0 +1,15 @@
+module 0x42::M {
+    struct D has copy, drop {
+        value: u64,
+    }
+
+    #[operator = b"-"]
+    native fun sub(a: D, b: D): num;
+
+    #[operator = b"+"]
+    native fun add(a: D, b: D): u64;
+
+    spec fun diff(a: D, b: D): num {
+        a - b
+    }
+}
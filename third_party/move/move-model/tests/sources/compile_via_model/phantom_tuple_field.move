@@ -0,0 +1,6 @@
+module 0x42::M {
+    struct S<phantom T> {
+        mixed: (T, u64),
+        all_phantom: (T, T),
+    }
+}
@@ -0,0 +1,9 @@
+module 0x42::M {
+    struct A<phantom T> has drop {
+        b: vector<B<vector<T>>>,
+    }
+
+    struct B<U> has drop {
+        a: vector<A<U>>,
+    }
+}
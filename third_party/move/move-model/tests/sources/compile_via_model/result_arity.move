@@ -0,0 +1,13 @@
+module 0x42::M {
+    struct S has drop { x: u64 }
+
+    fun none() {}
+
+    fun one(): u64 { 1 }
+
+    fun two(): (u64, bool) { (1, true) }
+
+    fun three<T: drop>(x: T): (T, S, vector<u8>) { (x, S { x: 1 }, vector[]) }
+
+    native fun native_two(): (address, u128);
+}
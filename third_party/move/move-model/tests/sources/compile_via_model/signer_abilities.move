@@ -0,0 +1,7 @@
+module 0x42::M {
+    struct NeedsCopy<T: copy> { x: T }
+
+    struct Holder { f: NeedsCopy<signer> }
+
+    struct Stored has store { s: signer }
+}
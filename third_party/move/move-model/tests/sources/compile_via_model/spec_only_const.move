@@ -0,0 +1,8 @@
+module 0x42::M {
+    fun f(): u64 { MAX_U64 }
+
+    fun g(x: u64): u64 { x }
+    spec g {
+        ensures result <= MAX_U64;
+    }
+}
@@ -0,0 +1,3 @@
+module 0x42::M {
+    struct S { ok: u64, bad: num }
+}
@@ -0,0 +1,9 @@
+module 0x42::M {
+    struct S<T1, T2> has drop { x: T1, y: T2 }
+
+    fun under(_s: S<u64>) {}
+
+    fun over() {
+        S<u64, u64, u64> { x: 1, y: 2 };
+    }
+}
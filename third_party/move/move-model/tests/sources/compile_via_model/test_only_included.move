@@ -0,0 +1,12 @@
+module 0x42::M {
+    #[test_only]
+    struct Fixture has drop { x: u64 }
+
+    #[test_only]
+    fun fixture(): Fixture { Fixture { x: 1 } }
+
+    #[test]
+    fun test_fixture() { fixture(); }
+
+    fun f() { fixture(); }
+}
@@ -0,0 +1,6 @@
+module 0x42::M {
+    fun f(s: Self::S): u64 {
+        let _ = s;
+        0
+    }
+}
@@ -0,0 +1,7 @@
+module 0x42::M {
+    struct NonDroppable {}
+
+    struct NeedsDrop<T: drop> has drop { x: T }
+
+    struct Holder { f: NeedsDrop<vector<NonDroppable>> }
+}
@@ -0,0 +1,6 @@
+module 0x42::M {
+    #[verify_only]
+    fun helper(): u64 { 1 }
+
+    fun f(): u64 { helper() }
+}
@@ -0,0 +1,11 @@
+module 0x42::A {
+    struct Token has copy, drop { value: u64 }
+}
+
+module 0x42::B {
+    use 0x42::A::Token;
+
+    struct Vault has key {
+        token: Token,
+    }
+}
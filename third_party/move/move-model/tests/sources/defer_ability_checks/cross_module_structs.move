@@ -0,0 +1,14 @@
+module 0x42::A {
+    struct Inner<T: store> has copy, drop, store {
+        x: T,
+    }
+}
+
+module 0x42::B {
+    use 0x42::A::Inner;
+
+    struct Outer has key {
+        inner: Inner<u64>,
+        inners: vector<Inner<bool>>,
+    }
+}
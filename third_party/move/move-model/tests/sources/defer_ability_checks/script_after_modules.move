@@ -0,0 +1,15 @@
+module 0x42::M {
+    struct Coin has drop { value: u64 }
+
+    public fun mint(value: u64): Coin {
+        Coin { value }
+    }
+}
+
+script {
+    use 0x42::M;
+
+    fun main() {
+        let _coin = M::mint(1);
+    }
+}
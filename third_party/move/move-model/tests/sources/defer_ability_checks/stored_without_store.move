@@ -0,0 +1,7 @@
+module 0x42::M {
+    struct Inner has drop { x: u64 }
+
+    struct Outer has key {
+        inner: Inner,
+    }
+}
@@ -0,0 +1,8 @@
+module 0x42::M {
+    struct Coin has copy, drop { value: u64 }
+
+    struct Wallet has store {
+        first: Coin,
+        second: vector<Coin>,
+    }
+}
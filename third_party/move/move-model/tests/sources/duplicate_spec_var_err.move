@@ -0,0 +1,6 @@
+module 0x42::M {
+    spec module {
+        global counter: u64;
+        global counter: bool;
+    }
+}
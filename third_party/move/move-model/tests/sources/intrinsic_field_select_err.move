@@ -0,0 +1,33 @@
+module 0x42::M {
+    struct MyTable<phantom K, phantom V> has store { handle: address }
+
+    native fun new<K, V>(): MyTable<K, V>;
+    native fun length<K, V>(t: &MyTable<K, V>): u64;
+
+    spec native fun spec_len<K, V>(t: MyTable<K, V>): num;
+
+    spec MyTable {
+        pragma intrinsic = map,
+            map_new = new,
+            map_len = length,
+            map_spec_len = spec_len;
+    }
+
+    // Selecting the fields of an intrinsic type is fine in code.
+    fun handle_of<K, V>(t: &MyTable<K, V>): address {
+        t.handle
+    }
+    spec handle_of {
+        ensures result == t.handle;
+    }
+
+    struct Holder has key { t: MyTable<u64, u64> }
+
+    spec fun holder_handle(h: Holder): address {
+        h.t.handle
+    }
+
+    spec fun holder_len(h: Holder): num {
+        spec_len(h.t)
+    }
+}
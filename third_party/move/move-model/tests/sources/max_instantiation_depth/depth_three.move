@@ -0,0 +1,4 @@
+module 0x42::M {
+    struct Box<T> has drop { value: T }
+    fun nest(b: Box<Box<u64>>): Box<Box<Box<u64>>> { Box { value: b } }
+}
@@ -0,0 +1,7 @@
+module 0x42::M {
+    struct Box<T> has drop { value: T }
+
+    struct Shallow has drop { b: Box<Box<u64>> }
+
+    struct Deep has drop { b: Box<Box<Box<u64>>> }
+}
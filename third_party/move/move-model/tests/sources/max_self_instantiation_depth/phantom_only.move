@@ -0,0 +1,13 @@
+module 0x42::M {
+    struct State<phantom T> has drop { count: u64 }
+
+    struct Pair<phantom T, U> has drop { value: U }
+
+    struct Ready {}
+
+    // All type parameters are phantom, so the nesting does not affect the layout.
+    struct Tagged has drop { s: State<State<State<Ready>>> }
+
+    // Only one type parameter is phantom, so the nesting is reported.
+    struct Nested has drop { p: Pair<Ready, Pair<Ready, Pair<Ready, u64>>> }
+}
@@ -0,0 +1,4 @@
+module 0x42::M {
+    struct Narrow { a: u64, b: u64 }
+    struct Wide { a: u64, b: u64, c: u64 }
+}
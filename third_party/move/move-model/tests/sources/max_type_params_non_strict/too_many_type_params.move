@@ -0,0 +1,5 @@
+module 0x42::M {
+    struct Pair<A, B> { a: A, b: B }
+
+    struct Triple<A, B, C> { a: A, b: B, c: C }
+}
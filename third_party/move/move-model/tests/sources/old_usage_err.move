@@ -0,0 +1,15 @@
+module 0x42::M {
+
+  fun incr(x: &mut u64) { *x = *x + 1 }
+
+  spec incr {
+    requires old(x) > 0;
+    ensures x == old(x) + 1;
+  }
+
+  fun read(r: &u64): u64 { *r }
+
+  spec read {
+    ensures result == old(r);
+  }
+}
@@ -0,0 +1,8 @@
+module 0x42::M {
+    struct Decimal has copy, drop {
+        value: u64,
+    }
+
+    #[operator = b"<"]
+    fun lt(a: Decimal, b: Decimal): u64 { a.value }
+}
@@ -0,0 +1,31 @@
+module 0x42::M {
+    struct Decimal has copy, drop {
+        value: u64,
+    }
+
+    #[operator = b"+"]
+    public fun add(a: Decimal, b: Decimal): Decimal { Decimal { value: a.value + b.value } }
+
+    #[operator = b"<"]
+    public fun lt(a: Decimal, b: Decimal): bool { a.value < b.value }
+
+    spec fun sum(a: Decimal, b: Decimal): Decimal {
+        a + b
+    }
+
+    spec fun smaller(a: Decimal, b: Decimal): bool {
+        a < b
+    }
+
+    spec fun plain_sum(a: u64, b: u64): u64 {
+        a + b
+    }
+}
+
+module 0x42::N {
+    use 0x42::M::Decimal;
+
+    spec fun sum_of_three(a: Decimal, b: Decimal, c: Decimal): Decimal {
+        a + b + c
+    }
+}
@@ -0,0 +1,23 @@
+module 0x42::M {
+    spec fun count(x: u64): u64 { x + 1 }
+
+    fun f(x: u64): u64 { x }
+    spec f {
+        requires count(x);
+    }
+
+    fun size(v: vector<u64>): vector<u64> { v }
+    spec size {
+        requires len(v);
+        requires size(v);
+    }
+}
+
+module 0x42::N {
+    fun len(x: u64): bool { x > 0 }
+
+    fun f(x: u64): u64 { x }
+    spec f {
+        requires len(x);
+    }
+}
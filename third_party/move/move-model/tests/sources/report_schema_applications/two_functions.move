@@ -0,0 +1,22 @@
+module 0x42::M {
+    fun f(x: u64): u64 { x + 1 }
+    spec f {
+        include Positive;
+    }
+
+    fun g(x: u64): u64 { x }
+    spec g {
+        include Positive;
+    }
+
+    fun h(x: u64): u64 { x }
+
+    spec module {
+        apply Positive to h;
+    }
+
+    spec schema Positive {
+        x: u64;
+        requires x > 0;
+    }
+}
@@ -0,0 +1,11 @@
+module 0x42::M {
+    const E_RESERVED: u64 = 150;
+    const E_OWN: u64 = 200;
+    const LIMIT: u8 = 100;
+
+    fun check(x: u64) {
+        if (x == 0) abort E_RESERVED;
+        if (x == 1) abort E_OWN;
+        if (x > (LIMIT as u64)) abort 1;
+    }
+}
@@ -0,0 +1,26 @@
+module 0x42::M {
+    fun f(x: u64, a: u64): u64 { x + a }
+
+    spec f {
+        include Outer;
+        include Renamed;
+    }
+
+    spec schema Inner {
+        x: u64;
+        let y = x + 1;
+        ensures y > x;
+    }
+
+    spec schema Outer {
+        x: u64;
+        let w = x;
+        include Inner;
+        ensures w == x;
+    }
+
+    spec schema Renamed {
+        a: u64;
+        include Inner { x: a };
+    }
+}
@@ -0,0 +1,5 @@
+module 0x42::M {
+    fun doThing() {}
+    fun dothing() {}
+    fun do_thing() {}
+}
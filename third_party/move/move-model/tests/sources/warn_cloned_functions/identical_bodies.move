@@ -0,0 +1,5 @@
+module 0x42::M {
+    fun f(x: u64, y: u64): u64 { let z = x + y; if (z > 10) z - 10 else z * 2 }
+    fun g(a: u64, b: u64): u64 { let c = a + b; if (c > 10) c - 10 else c * 2 }
+    fun h(x: u64, y: u64): u64 { let z = x * y; if (z > 10) z - 10 else z * 2 }
+}
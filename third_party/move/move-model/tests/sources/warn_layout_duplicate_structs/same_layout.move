@@ -0,0 +1,6 @@
+module 0x42::M {
+    struct Coin has copy, drop { value: u64, frozen: bool }
+    struct Token has drop { amount: u64, locked: bool }
+    struct Pair has copy, drop { first: u64, second: u64 }
+    struct Other has copy, drop { a: u64, b: u64 }
+}
@@ -0,0 +1,16 @@
+module 0x42::M {
+    struct Coin has drop { value: u64 }
+    struct Handle has drop { id: u64 }
+
+    public fun mint(value: u64): Coin {
+        Coin { value }
+    }
+
+    fun new_handle(id: u64): Handle {
+        Handle { id }
+    }
+
+    public fun handle_id(): u64 {
+        new_handle(1).id
+    }
+}
@@ -0,0 +1,16 @@
+module 0x42::M {
+    // A linear call chain, none of these functions is recursive.
+    fun a(x: u64): u64 { b(x) + 1 }
+
+    fun b(x: u64): u64 { c(x) + 1 }
+
+    fun c(x: u64): u64 { x }
+
+    // A cycle of two functions.
+    fun even(n: u64): bool { if (n == 0) true else odd(n - 1) }
+
+    fun odd(n: u64): bool { if (n == 0) false else even(n - 1) }
+
+    // A function calling itself.
+    fun fact(n: u64): u64 { if (n == 0) 1 else n * fact(n - 1) }
+}
@@ -0,0 +1,13 @@
+module 0x42::M {
+    const E_NOT_FOUND: u64 = 1;
+    const KIND: u8 = 2;
+
+    fun f(x: u64) {
+        if (x == 0) abort E_NOT_FOUND;
+        if (x == 1) abort 2;
+    }
+
+    spec f {
+        aborts_with 1, 2;
+    }
+}
@@ -0,0 +1,8 @@
+module 0x42::M {
+    spec module {
+        fun sum(n: num): num { if (n == 0) { 0 } else { n + sum(n - 1) } }
+
+        #[decreases]
+        fun sum_hinted(n: num): num { if (n == 0) { 0 } else { n + sum_hinted(n - 1) } }
+    }
+}
@@ -0,0 +1,7 @@
+module 0x42::M {
+    public fun specced(x: u64): u64 { x }
+    spec specced { ensures result == x; }
+    public fun inline_specced(x: u64): u64 { spec { assert x == x; }; x }
+    public fun unspecced(x: u64): u64 { x }
+    fun internal(x: u64): u64 { x }
+}
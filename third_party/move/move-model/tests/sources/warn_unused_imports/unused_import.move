@@ -0,0 +1,9 @@
+module 0x42::A {
+    public fun f(): u64 { 1 }
+}
+
+module 0x42::B {
+    use 0x42::A;
+
+    fun g(): u64 { 2 }
+}
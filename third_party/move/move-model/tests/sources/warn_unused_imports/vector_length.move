@@ -0,0 +1,11 @@
+module 0x1::vector {
+    native public fun length<Element>(v: &vector<Element>): u64;
+}
+
+module 0x42::M {
+    use 0x1::vector;
+
+    fun len(v: vector<u64>): u64 {
+        vector::length(&v)
+    }
+}
@@ -0,0 +1,12 @@
+module 0x42::M {
+    fun first(x: u64, y: u64, _z: u64): u64 {
+        x
+    }
+
+    fun with_spec(a: u64, b: u64): u64 {
+        spec {
+            assert b > 0;
+        };
+        a
+    }
+}
@@ -0,0 +1,12 @@
+module 0x42::M {
+    fun f(x: u64): u64 { x + 1 }
+    spec f {
+        ensures result == inc(x);
+    }
+
+    spec module {
+        fun inc(x: u64): u64 { x + 1 }
+        fun dec(x: u64): u64 { x - 1 }
+        fun _helper(x: u64): u64 { x }
+    }
+}
@@ -0,0 +1,6 @@
+module 0x42::M {
+    struct Pool has store {
+        id: u64,
+        total: u256,
+    }
+}
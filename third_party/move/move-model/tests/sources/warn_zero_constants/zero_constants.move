@@ -0,0 +1,8 @@
+module 0x42::M {
+    const ZERO: u64 = 0;
+    const ONE: u64 = 1;
+
+    fun get(): u64 {
+        ZERO + ONE
+    }
+}
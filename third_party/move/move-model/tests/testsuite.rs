@@ -7,24 +7,58 @@ use move_command_line_common::testing::EXP_EXT;
 use move_compiler::shared::{known_attributes::KnownAttribute, PackagePaths};
 use move_model::{options::ModelBuilderOptions, run_model_builder_with_options};
 use move_prover_test_utils::baseline_test::verify_or_update_baseline;
-use std::path::Path;
+use move_symbol_pool::Symbol;
+use std::{collections::BTreeMap, path::Path};
 
 fn test_runner(path: &Path, options: ModelBuilderOptions) -> datatest_stable::Result<()> {
+    test_runner_with_severity(path, options, Severity::Warning)
+}
+
+/// Like `test_runner`, but reports diagnostics down to the given severity. This is used for
+/// options which generate notes.
+fn test_runner_with_severity(
+    path: &Path,
+    options: ModelBuilderOptions,
+    severity: Severity,
+) -> datatest_stable::Result<()> {
     let targets = vec![PackagePaths {
         name: None,
         paths: vec![path.to_str().unwrap().to_string()],
-        named_address_map: std::collections::BTreeMap::<String, _>::new(),
+        named_address_map: BTreeMap::new(),
     }];
+    test_runner_with_packages(path, targets, vec![], options, severity)
+}
+
+/// Runs the test for `path` with the given target and dependency packages, reporting
+/// diagnostics down to the given severity.
+fn test_runner_with_packages(
+    path: &Path,
+    targets: Vec<PackagePaths<String, String>>,
+    deps: Vec<PackagePaths<String, String>>,
+    options: ModelBuilderOptions,
+    severity: Severity,
+) -> datatest_stable::Result<()> {
     let env = run_model_builder_with_options(
         targets,
-        vec![],
+        deps,
         options,
         false,
         KnownAttribute::get_all_attribute_names(),
     )?;
-    let diags = if env.diag_count(Severity::Warning) > 0 {
+    if !env.has_errors() {
+        // The abilities precomputed while building the model agree with the declared ones.
+        for module in env.get_modules() {
+            for struct_env in module.get_structs() {
+                assert_eq!(
+                    env.get_struct_abilities(struct_env.get_qualified_id()),
+                    Some(struct_env.get_abilities())
+                );
+            }
+        }
+    }
+    let diags = if env.diag_count(severity) > 0 {
         let mut writer = Buffer::no_color();
-        env.report_diag(&mut writer, Severity::Warning);
+        env.report_diag(&mut writer, severity);
         String::from_utf8_lossy(&writer.into_inner()).to_string()
     } else {
         "All good, no errors!".to_string()
@@ -34,14 +68,303 @@ fn test_runner(path: &Path, options: ModelBuilderOptions) -> datatest_stable::Re
     Ok(())
 }
 
+/// Runs the test for `path` as the package `target`, with the files in the sibling directory
+/// `deps` forming the dependency package `other`.
+fn test_runner_with_deps_package(
+    path: &Path,
+    options: ModelBuilderOptions,
+    severity: Severity,
+) -> datatest_stable::Result<()> {
+    let package = |name: &str, path: &Path| PackagePaths {
+        name: Some(Symbol::from(name)),
+        paths: vec![path.to_str().unwrap().to_string()],
+        named_address_map: BTreeMap::new(),
+    };
+    test_runner_with_packages(
+        path,
+        vec![package("target", path)],
+        vec![package("other", &path.parent().unwrap().join("deps"))],
+        options,
+        severity,
+    )
+}
+
+/// The test directories which need options other than the default ones, with the options and
+/// the minimal severity of reported diagnostics. A test is run with the first entry whose
+/// directory is a component of its path.
+fn test_configs() -> Vec<(&'static str, ModelBuilderOptions, Severity)> {
+    use Severity::{Note, Warning};
+    let via_model = || ModelBuilderOptions {
+        compile_via_model: true,
+        ..Default::default()
+    };
+    vec![
+        ("compile_via_model", via_model(), Warning),
+        (
+            "defer_ability_checks",
+            ModelBuilderOptions {
+                defer_ability_checks: true,
+                ..via_model()
+            },
+            Note,
+        ),
+        (
+            "exclude_verify_only_code",
+            ModelBuilderOptions {
+                exclude_verify_only_code: true,
+                ..via_model()
+            },
+            Warning,
+        ),
+        (
+            "exclude_test_code",
+            ModelBuilderOptions {
+                exclude_test_code: true,
+                ..via_model()
+            },
+            Warning,
+        ),
+        (
+            "warn_layout_duplicate_structs",
+            ModelBuilderOptions {
+                warn_layout_duplicate_structs: true,
+                ..Default::default()
+            },
+            Note,
+        ),
+        (
+            "warn_cloned_functions",
+            ModelBuilderOptions {
+                warn_cloned_functions: true,
+                ..via_model()
+            },
+            Note,
+        ),
+        (
+            "warn_recursive_functions",
+            ModelBuilderOptions {
+                warn_recursive_functions: true,
+                ..via_model()
+            },
+            Note,
+        ),
+        (
+            "warn_unused_params",
+            ModelBuilderOptions {
+                warn_unused_params: true,
+                ..via_model()
+            },
+            Note,
+        ),
+        (
+            "warn_unused_spec_funs",
+            ModelBuilderOptions {
+                warn_unused_spec_funs: true,
+                ..Default::default()
+            },
+            Note,
+        ),
+        (
+            "warn_unspecified_public_funs",
+            ModelBuilderOptions {
+                warn_unspecified_public_funs: true,
+                ..Default::default()
+            },
+            Note,
+        ),
+        (
+            "report_schema_applications",
+            ModelBuilderOptions {
+                report_schema_applications: true,
+                ..Default::default()
+            },
+            Note,
+        ),
+        (
+            "check_public_api_types",
+            ModelBuilderOptions {
+                check_public_api_types: true,
+                ..Default::default()
+            },
+            Note,
+        ),
+        (
+            "warn_opaque_structs",
+            ModelBuilderOptions {
+                warn_opaque_structs: true,
+                ..Default::default()
+            },
+            Note,
+        ),
+        (
+            "warn_unknown_abort_codes",
+            ModelBuilderOptions {
+                warn_unknown_abort_codes: true,
+                ..Default::default()
+            },
+            Note,
+        ),
+        (
+            "warn_unproven_spec_fun_termination",
+            ModelBuilderOptions {
+                warn_unproven_spec_fun_termination: true,
+                ..Default::default()
+            },
+            Note,
+        ),
+        (
+            "check_attributes",
+            ModelBuilderOptions {
+                check_attributes: true,
+                ..Default::default()
+            },
+            Note,
+        ),
+        (
+            "check_resource_addresses",
+            ModelBuilderOptions {
+                check_resource_addresses: true,
+                ..Default::default()
+            },
+            Note,
+        ),
+        (
+            "warn_wide_integer_fields",
+            ModelBuilderOptions {
+                warn_wide_integer_fields: true,
+                ..Default::default()
+            },
+            Note,
+        ),
+        ("builtin_name_arity", ModelBuilderOptions::default(), Note),
+        (
+            "check_const_ranges",
+            ModelBuilderOptions {
+                check_const_ranges: true,
+                ..via_model()
+            },
+            Warning,
+        ),
+        (
+            "check_type_param_abilities",
+            ModelBuilderOptions {
+                check_type_param_abilities: true,
+                ..via_model()
+            },
+            Warning,
+        ),
+        (
+            "builtin_module_name",
+            ModelBuilderOptions {
+                builtin_module_name: Some("$builtins".to_string()),
+                ..Default::default()
+            },
+            Warning,
+        ),
+        (
+            "reserved_error_code_ranges",
+            ModelBuilderOptions {
+                reserved_error_code_ranges: vec![(100, 199)],
+                ..Default::default()
+            },
+            Note,
+        ),
+        (
+            "warn_case_collisions",
+            ModelBuilderOptions {
+                warn_case_collisions: true,
+                ..Default::default()
+            },
+            Note,
+        ),
+        (
+            "warn_zero_constants",
+            ModelBuilderOptions {
+                warn_zero_constants: true,
+                ..Default::default()
+            },
+            Note,
+        ),
+        (
+            "warn_unused_imports",
+            ModelBuilderOptions {
+                warn_unused_imports: true,
+                ..via_model()
+            },
+            Note,
+        ),
+        (
+            "warn_unused_imports_v1",
+            ModelBuilderOptions {
+                warn_unused_imports: true,
+                ..Default::default()
+            },
+            Note,
+        ),
+        (
+            "max_self_instantiation_depth",
+            ModelBuilderOptions {
+                max_self_instantiation_depth: Some(2),
+                ..Default::default()
+            },
+            Note,
+        ),
+        (
+            "max_struct_fields",
+            ModelBuilderOptions {
+                max_struct_fields: Some(2),
+                ..Default::default()
+            },
+            Note,
+        ),
+        (
+            "max_instantiation_depth",
+            ModelBuilderOptions {
+                max_instantiation_depth: Some(2),
+                ..Default::default()
+            },
+            Note,
+        ),
+        (
+            "max_type_params",
+            ModelBuilderOptions {
+                max_type_params: Some(2),
+                strict_max_type_params: true,
+                ..Default::default()
+            },
+            Warning,
+        ),
+        (
+            "max_type_params_non_strict",
+            ModelBuilderOptions {
+                max_type_params: Some(2),
+                ..Default::default()
+            },
+            Note,
+        ),
+    ]
+}
+
+/// The test directories whose sibling directory `deps` forms a dependency package, see
+/// `test_runner_with_deps_package`.
+const DEPS_PACKAGE_DIRS: &[&str] = &["check_public_api_types"];
+
 fn runner(path: &Path) -> datatest_stable::Result<()> {
-    if path.display().to_string().contains("/compile_via_model/") {
-        test_runner(path, ModelBuilderOptions {
-            compile_via_model: true,
-            ..Default::default()
-        })
-    } else {
-        test_runner(path, ModelBuilderOptions::default())
+    let path_str = path.display().to_string();
+    let config = test_configs()
+        .into_iter()
+        .find(|(dir, _, _)| path_str.contains(&format!("/{}/", dir)));
+    match config {
+        Some((dir, options, severity)) if DEPS_PACKAGE_DIRS.contains(&dir) => {
+            if path_str.contains(&format!("/{}/deps/", dir)) {
+                // The files of the dependency package are also checked on their own.
+                test_runner(path, ModelBuilderOptions::default())
+            } else {
+                test_runner_with_deps_package(path, options, severity)
+            }
+        },
+        Some((_, options, severity)) => test_runner_with_severity(path, options, severity),
+        None => test_runner(path, ModelBuilderOptions::default()),
     }
 }
 
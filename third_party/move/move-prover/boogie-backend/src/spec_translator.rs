@@ -748,6 +748,10 @@ impl<'env> SpecTranslator<'env> {
                 let loc = self.env.get_node_loc(node_id);
                 self.error(&loc, &format!("tuple value not yet supported: {:#?}", val))
             },
+            Value::Error => {
+                let loc = self.env.get_node_loc(node_id);
+                self.error(&loc, "erroneous value")
+            },
         }
     }
 